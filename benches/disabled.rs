@@ -0,0 +1,54 @@
+//! Measures the overhead of the instrumentation when the log levels are disabled, run with `cargo bench`.
+//! No logger is installed, so every level is disabled and the instrumented functions should cost about
+//! the same as the plain one.
+
+use log_derive::{logfn, logfn_inputs};
+use std::time::{Duration, Instant};
+
+const ITERATIONS: u32 = 10_000_000;
+
+fn plain(a: u64, b: u64) -> u64 {
+    a.wrapping_mul(b)
+}
+
+#[logfn(Info)]
+#[logfn_inputs(Debug)]
+fn instrumented(a: u64, b: u64) -> u64 {
+    a.wrapping_mul(b)
+}
+
+#[logfn(Info, log_ts = true)]
+fn timed(a: u64, b: u64) -> u64 {
+    a.wrapping_mul(b)
+}
+
+#[logfn(ok = "Info", err = "Error", log_ts = true, every = 10)]
+fn sampled(a: u64, b: u64) -> Result<u64, u64> {
+    a.checked_mul(b).ok_or(a)
+}
+
+/// Hides the value from the optimizer, `std::hint::black_box` needs Rust 1.66.
+fn black_box(value: u64) -> u64 {
+    unsafe { std::ptr::read_volatile(&value) }
+}
+
+fn bench(name: &str, f: impl Fn(u64, u64) -> u64) -> Duration {
+    let start = Instant::now();
+    for i in 0..ITERATIONS {
+        black_box(f(black_box(u64::from(i)), black_box(3)));
+    }
+    let elapsed = start.elapsed();
+    println!("{:<14} {:>8.2} ns/iter", name, elapsed.as_nanos() as f64 / f64::from(ITERATIONS));
+    elapsed
+}
+
+fn main() {
+    let baseline = bench("plain", plain);
+    for (name, elapsed) in [
+        ("instrumented", bench("instrumented", instrumented)),
+        ("timed", bench("timed", timed)),
+        ("sampled", bench("sampled", |a, b| sampled(a, b).unwrap_or(0))),
+    ] {
+        println!("{:<14} {:>8.2}x plain", name, elapsed.as_secs_f64() / baseline.as_secs_f64());
+    }
+}
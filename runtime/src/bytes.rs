@@ -0,0 +1,49 @@
+//! Compact renderings of binary values, used by the `hex` and `base64` options.
+//!
+//! Both accept anything that's `AsRef<[u8]>`, like `&[u8]`, `Vec<u8>` and `[u8; N]`,
+//! and render the same way with `{}` and `{:?}`.
+
+use std::fmt::{self, Debug, Display, Write};
+
+/// Renders the bytes as lowercase hex, `deadbeef`.
+pub struct Hex<'a, T: ?Sized>(pub &'a T);
+
+impl<T: AsRef<[u8]> + ?Sized> Display for Hex<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.as_ref().iter().try_for_each(|byte| write!(f, "{:02x}", byte))
+    }
+}
+
+impl<T: AsRef<[u8]> + ?Sized> Debug for Hex<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+/// Renders the bytes as padded standard base64, `3q2+7w==`.
+pub struct Base64<'a, T: ?Sized>(pub &'a T);
+
+const BASE64_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+impl<T: AsRef<[u8]> + ?Sized> Display for Base64<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for chunk in self.0.as_ref().chunks(3) {
+            let bits = chunk.iter().enumerate().fold(0u32, |bits, (i, byte)| bits | u32::from(*byte) << (16 - 8 * i));
+            // Every 3 bytes are 4 characters, a shorter chunk is padded with `=`.
+            for i in 0..4 {
+                if i <= chunk.len() {
+                    f.write_char(char::from(BASE64_ALPHABET[(bits >> (18 - 6 * i) & 0x3f) as usize]))?;
+                } else {
+                    f.write_char('=')?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T: AsRef<[u8]> + ?Sized> Debug for Base64<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+}
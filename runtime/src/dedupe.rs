@@ -0,0 +1,63 @@
+//! Collapsing of repeated messages, used by the `dedupe` option.
+//!
+//! Every function instrumented with `dedupe = "WINDOW"` remembers the last message it logged, the same message logged
+//! again (at the same level) within the window of its first occurrence is suppressed and counted. <br>
+//! Once a different message is logged, or the same one after the window, the count is reported first:
+//! `"fetch() => Timeout (repeated 99 times)"`.
+
+use log::Level;
+use std::fmt::{self, Display};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// The state shared by the log statements of a function.
+pub struct Dedupe {
+    window: Duration,
+    last: Mutex<Option<Last>>,
+}
+
+struct Last {
+    level: Level,
+    msg: String,
+    since: Instant,
+    repeated: u64,
+}
+
+impl Dedupe {
+    pub const fn new(window: Duration) -> Self {
+        Dedupe { window, last: Mutex::new(None) }
+    }
+
+    /// Logs `msg` through `log`, unless it repeats the last message within the window.
+    /// The count of the previous message is logged at its own level, which is why `log` takes the level.
+    /// The lock is released before logging, so a logger which itself logs through this function can't deadlock.
+    pub fn log(&self, level: Level, msg: String, mut log: impl FnMut(Level, &dyn Display)) {
+        let now = Instant::now();
+        let previous = {
+            let mut last = self.last.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            match last.as_mut() {
+                Some(last) if last.level == level && last.msg == msg && now.duration_since(last.since) < self.window => {
+                    last.repeated += 1;
+                    return;
+                }
+                _ => last.replace(Last { level, msg: msg.clone(), since: now, repeated: 0 }),
+            }
+        };
+        if let Some(Last { level, msg, repeated, .. }) = previous.filter(|previous| previous.repeated > 0) {
+            log(level, &Repeated { msg, repeated });
+        }
+        log(level, &msg);
+    }
+}
+
+/// The message reporting how many times the previous one was suppressed.
+struct Repeated {
+    msg: String,
+    repeated: u64,
+}
+
+impl Display for Repeated {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} (repeated {} times)", self.msg, self.repeated)
+    }
+}
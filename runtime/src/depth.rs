@@ -0,0 +1,80 @@
+//! Nesting depth of instrumented calls, used by the `depth` option to log a call tree.
+//!
+//! The depth is kept in a thread local, synchronous functions increment it until they return
+//! and async functions set it while their body is being polled, so it follows the task and not the thread.
+
+use std::cell::Cell;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+thread_local! {static DEPTH: Cell<usize> = const { Cell::new(0) };}
+
+/// The depth of the instrumented call currently running on this thread.
+pub fn current() -> usize {
+    DEPTH.with(Cell::get)
+}
+
+/// Increments the depth for the lifetime of the returned guard.
+pub fn enter() -> DepthGuard {
+    let depth = current();
+    DEPTH.with(|cell| cell.set(depth + 1));
+    DepthGuard { depth }
+}
+
+/// Restores the depth to what it was before [`enter`] when dropped.
+pub struct DepthGuard {
+    depth: usize,
+}
+
+impl DepthGuard {
+    /// The depth of the call that created this guard.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+}
+
+impl Drop for DepthGuard {
+    fn drop(&mut self) {
+        DEPTH.with(|cell| cell.set(self.depth));
+    }
+}
+
+/// Runs `future` with the depth set to `depth` whenever it's polled.
+pub fn scope<F: Future>(depth: usize, future: F) -> Scope<F> {
+    Scope { depth, future: Box::pin(future) }
+}
+
+/// The future returned by [`scope`].
+pub struct Scope<F> {
+    depth: usize,
+    future: Pin<Box<F>>,
+}
+
+impl<F: Future> Future for Scope<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let _guard = DepthGuard { depth: DEPTH.with(|cell| cell.replace(self.depth)) };
+        self.future.as_mut().poll(cx)
+    }
+}
+
+/// Renders the depth as two spaces per level, for `depth = "indent"`.
+pub struct Indent(pub usize);
+
+impl fmt::Display for Indent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:1$}", "", self.0 * 2)
+    }
+}
+
+/// Renders the depth as `depth=N `, for `depth = "number"`.
+pub struct Number(pub usize);
+
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "depth={} ", self.0)
+    }
+}
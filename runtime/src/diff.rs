@@ -0,0 +1,32 @@
+//! Changes of mutable inputs between the entry and the exit of a call, used by the `diff` option.
+
+use std::fmt::{self, Debug, Display};
+
+/// Renders `unchanged` if the value is equal to its snapshot from before the call, or `before -> after` if it isn't.
+pub struct Diff<'a, T> {
+    before: Option<&'a T>,
+    after: &'a T,
+}
+
+impl<'a, T> Diff<'a, T> {
+    /// The snapshot is `None` if it wasn't taken because the message was disabled, then only `after` is rendered.
+    pub fn new(before: Option<&'a T>, after: &'a T) -> Self {
+        Diff { before, after }
+    }
+}
+
+impl<T: Debug + PartialEq> Display for Diff<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.before {
+            Some(before) if before == self.after => f.write_str("unchanged"),
+            Some(before) => write!(f, "{:?} -> {:?}", before, self.after),
+            None => write!(f, "{:?}", self.after),
+        }
+    }
+}
+
+impl<T: Debug + PartialEq> Debug for Diff<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+}
@@ -0,0 +1,56 @@
+//! Causal chains of errors, used by the `err_chain` option.
+//!
+//! The generated code calls `err.error_chain()` with both [`ErrorChain`] and [`BoxedErrorChain`] in scope,
+//! only one of them is implemented for any error type so it works for errors and boxed errors alike.
+
+use std::error::Error;
+use std::fmt::{self, Debug, Display};
+
+/// Renders an error followed by each of its sources, `outer, caused by: inner`.
+pub struct Chain<'a>(&'a (dyn Error + 'static));
+
+impl Display for Chain<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)?;
+        let mut source = self.0.source();
+        while let Some(error) = source {
+            write!(f, ", caused by: {}", error)?;
+            source = error.source();
+        }
+        Ok(())
+    }
+}
+
+impl Debug for Chain<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+/// The chain of an error type.
+pub trait ErrorChain {
+    fn error_chain(&self) -> Chain<'_>;
+}
+
+impl<E: Error + 'static> ErrorChain for E {
+    fn error_chain(&self) -> Chain<'_> {
+        Chain(self)
+    }
+}
+
+/// The chain of a boxed error, which doesn't implement [`Error`] itself.
+pub trait BoxedErrorChain {
+    fn error_chain(&self) -> Chain<'_>;
+}
+
+impl BoxedErrorChain for Box<dyn Error + 'static> {
+    fn error_chain(&self) -> Chain<'_> {
+        Chain(self.as_ref())
+    }
+}
+
+impl BoxedErrorChain for Box<dyn Error + Send + Sync + 'static> {
+    fn error_chain(&self) -> Chain<'_> {
+        Chain(self.as_ref())
+    }
+}
@@ -0,0 +1,15 @@
+//! # Log Derive Runtime
+//!
+//! Runtime support for the opt-in features of [`log-derive`] that need state shared between instrumented functions. <br>
+//! The code generated by the macros calls into this crate, so it only needs to be a dependency of crates using these features. <br>
+//!
+//! [`log-derive`]: https://docs.rs/log-derive
+
+pub mod bytes;
+pub mod dedupe;
+pub mod depth;
+pub mod diff;
+pub mod error;
+pub mod maybe_debug;
+pub mod registry;
+pub mod truncate;
@@ -0,0 +1,49 @@
+//! Formatting of values which might not implement `Debug`, used by the `maybe_debug` option.
+//!
+//! It's picked by autoref specialization: `(&Wrap(&value)).maybe_debug()` resolves to [`ViaDebug`] when the type of the value
+//! is known to implement `Debug`, and to [`ViaTypeName`] otherwise, which renders the type name: `<my_crate::Item>`. <br>
+//! The choice is made where the method is called, so inside a generic function a type parameter without a `Debug` bound
+//! always falls back to its type name, even if the actual type implements `Debug`.
+
+use std::any;
+use std::fmt::{self, Debug};
+
+/// The value being formatted, its methods come from the traits which have to be in scope.
+pub struct Wrap<'a, T>(pub &'a T);
+
+/// Either the value or its type name, formatted with `Debug`.
+pub enum MaybeDebug<'a> {
+    Debug(&'a dyn Debug),
+    TypeName(&'static str),
+}
+
+impl Debug for MaybeDebug<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MaybeDebug::Debug(value) => value.fmt(f),
+            MaybeDebug::TypeName(name) => write!(f, "<{}>", name),
+        }
+    }
+}
+
+/// Chosen when the value implements `Debug`.
+pub trait ViaDebug {
+    fn maybe_debug(&self) -> MaybeDebug<'_>;
+}
+
+impl<T: Debug> ViaDebug for Wrap<'_, T> {
+    fn maybe_debug(&self) -> MaybeDebug<'_> {
+        MaybeDebug::Debug(self.0)
+    }
+}
+
+/// The fallback, implemented for a reference so it's only found after autoref.
+pub trait ViaTypeName {
+    fn maybe_debug(&self) -> MaybeDebug<'_>;
+}
+
+impl<T> ViaTypeName for &Wrap<'_, T> {
+    fn maybe_debug(&self) -> MaybeDebug<'_> {
+        MaybeDebug::TypeName(any::type_name::<T>())
+    }
+}
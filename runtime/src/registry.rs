@@ -0,0 +1,124 @@
+//! Runtime log level overrides of instrumented functions, used by the `registry` option.
+//!
+//! Every log statement generated with `registry = true` has a [`Site`] holding the full name of its function
+//! (`module::path::function`) and its level, which registers itself the first time it's reached. <br>
+//! The maximum level of a function can then be raised, lowered or disabled with [`set_level`], or through the `LOG_DERIVE`
+//! environment variable which is read once, on the first lookup, and uses the same syntax as [`parse`]:
+//! `LOG_DERIVE=my_crate::db::query=trace,my_crate::net=off`. <br>
+//! Like a `RUST_LOG` directive an override only filters, every message keeps its own level and is logged if that level
+//! is enabled by the override (instead of by `log::max_level()`), and `off` disables the function. <br>
+//! An override applies to the function with that exact name, or to every function in the module with that path,
+//! and when several overrides match the longest one wins.
+
+use log::{Level, LevelFilter};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard, Once};
+
+/// The environment variable the overrides are read from.
+pub const ENV_VAR: &str = "LOG_DERIVE";
+
+/// Incremented whenever the overrides change, so every [`Site`] knows its cached level is stale.
+static GENERATION: AtomicUsize = AtomicUsize::new(1);
+static OVERRIDES: Mutex<Vec<(String, LevelFilter)>> = Mutex::new(Vec::new());
+static SITES: Mutex<Vec<Registered>> = Mutex::new(Vec::new());
+static ENV: Once = Once::new();
+
+/// A function registered by one of its log statements, as returned by [`registered`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Registered {
+    /// The full name of the function, `module::path::function`.
+    pub name: &'static str,
+    /// The level the log statement logs at.
+    pub level: Level,
+}
+
+/// A single generated log statement.
+pub struct Site {
+    name: &'static str,
+    level: Level,
+    registered: Once,
+    /// The generation the override was resolved at, `0` if it never was.
+    generation: AtomicUsize,
+    /// `0` when there's no override, otherwise the overriding filter plus one.
+    filter: AtomicUsize,
+}
+
+impl Site {
+    pub const fn new(name: &'static str, level: Level) -> Self {
+        Site { name, level, registered: Once::new(), generation: AtomicUsize::new(0), filter: AtomicUsize::new(0) }
+    }
+
+    /// Whether this statement should log, by the override of its function or else by `log::max_level()`.
+    pub fn enabled(&self) -> bool {
+        let generation = GENERATION.load(Ordering::Acquire);
+        if self.generation.load(Ordering::Acquire) != generation {
+            self.registered.call_once(|| {
+                // Every function has several sites of the same level, e.g. the message and the check skipping its work.
+                let registered = Registered { name: self.name, level: self.level };
+                let mut sites = lock(&SITES);
+                if !sites.contains(&registered) {
+                    sites.push(registered);
+                }
+            });
+            let filter = lookup(self.name).map_or(0, |filter| filter as usize + 1);
+            self.filter.store(filter, Ordering::Relaxed);
+            self.generation.store(generation, Ordering::Release);
+        }
+        let filter = match self.filter.load(Ordering::Relaxed) {
+            0 => log::max_level(),
+            filter => LevelFilter::iter().nth(filter - 1).unwrap_or(LevelFilter::Off),
+        };
+        self.level <= filter
+    }
+}
+
+/// Overrides the maximum level of a function, or of every function in a module.
+pub fn set_level(name: &str, level: LevelFilter) {
+    init_env();
+    let mut overrides = lock(&OVERRIDES);
+    overrides.retain(|(existing, _)| existing != name);
+    overrides.push((name.to_owned(), level));
+    GENERATION.fetch_add(1, Ordering::AcqRel);
+}
+
+/// Removes an override added by [`set_level`] or the environment variable.
+pub fn reset_level(name: &str) {
+    init_env();
+    lock(&OVERRIDES).retain(|(existing, _)| existing != name);
+    GENERATION.fetch_add(1, Ordering::AcqRel);
+}
+
+/// The functions and levels of the log statements which were reached at least once.
+pub fn registered() -> Vec<Registered> {
+    lock(&SITES).clone()
+}
+
+/// Parses a comma separated list of `name=level` overrides, entries which can't be parsed are skipped.
+pub fn parse(overrides: &str) -> Vec<(String, LevelFilter)> {
+    overrides
+        .split(',')
+        .filter_map(|entry| {
+            let split = entry.rfind('=')?;
+            Some((entry[..split].trim().to_owned(), entry[split + 1..].trim().parse().ok()?))
+        })
+        .collect()
+}
+
+fn init_env() {
+    ENV.call_once(|| {
+        if let Ok(value) = std::env::var(ENV_VAR) {
+            lock(&OVERRIDES).extend(parse(&value));
+        }
+    });
+}
+
+fn lookup(name: &str) -> Option<LevelFilter> {
+    init_env();
+    let matches = |prefix: &str| name == prefix || name.starts_with(prefix) && name[prefix.len()..].starts_with("::");
+    lock(&OVERRIDES).iter().filter(|(prefix, _)| matches(prefix)).max_by_key(|(prefix, _)| prefix.len()).map(|(_, level)| *level)
+}
+
+/// The registry is still usable after a panic while it was locked, as it's never left half updated.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
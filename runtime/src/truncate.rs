@@ -0,0 +1,64 @@
+//! Size limits for logged values, used by the `max_len` option.
+//!
+//! The value is formatted straight into the log message through a bounded writer which drops everything
+//! after the limit, so a huge value is never rendered into a buffer, only measured.
+
+use std::fmt::{self, Debug, Display, Write};
+
+/// Formats a value with at most `max_len` bytes, followed by an ellipsis and the full length if it's longer.
+pub struct Truncate<'a, T: ?Sized> {
+    value: &'a T,
+    max_len: usize,
+}
+
+impl<'a, T: ?Sized> Truncate<'a, T> {
+    pub fn new(value: &'a T, max_len: usize) -> Self {
+        Truncate { value, max_len }
+    }
+
+    fn write(&self, f: &mut fmt::Formatter, write: impl FnOnce(&mut Bounded) -> fmt::Result) -> fmt::Result {
+        let mut bounded = Bounded { f, remaining: self.max_len, len: 0 };
+        write(&mut bounded)?;
+        let len = bounded.len;
+        if len > self.max_len {
+            write!(f, "… ({} bytes)", len)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: Debug + ?Sized> Debug for Truncate<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let alternate = f.alternate();
+        self.write(f, |bounded| if alternate { write!(bounded, "{:#?}", self.value) } else { write!(bounded, "{:?}", self.value) })
+    }
+}
+
+impl<T: Display + ?Sized> Display for Truncate<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write(f, |bounded| write!(bounded, "{}", self.value))
+    }
+}
+
+/// Writes up to `remaining` bytes to the formatter and counts the rest.
+struct Bounded<'a, 'b> {
+    f: &'a mut fmt::Formatter<'b>,
+    remaining: usize,
+    len: usize,
+}
+
+impl Write for Bounded<'_, '_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.len += s.len();
+        if self.remaining > 0 {
+            let mut end = s.len().min(self.remaining);
+            while !s.is_char_boundary(end) {
+                end -= 1;
+            }
+            self.f.write_str(&s[..end])?;
+            // Once a character didn't fit nothing more is written, even if a shorter one would.
+            self.remaining = if end < s.len() { 0 } else { self.remaining - end };
+        }
+        Ok(())
+    }
+}
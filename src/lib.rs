@@ -4,6 +4,9 @@
 //!
 //! `log-derive` provides a simple attribute macro that facilitates logs as part of the [`log`] facade <br>
 //! Right now it contains two macros [`logfn`], [`logfn_inputs`] these macros are only for functions but still have a lot of power.
+//! To instrument all the functions of an `impl` block, `trait` or inline `mod` at once use [`logfn_all`].
+//! Closures can be instrumented with [`logclosure!`], which accepts the same options followed by the closure.
+//! A single block inside a function can be logged with [`log_block!`].
 //!
 //!
 //!  # Use
@@ -17,18 +20,102 @@
 //! In [`logfn`] if the function returns a [`Result`] type the macro will accept the following additional attributes: <br>
 //! `(ok = "LEVEL")` and `(err = "LEVEL")` this can provide different log levels if the function failed or not. <br>
 //!
+//! If the function returns `impl Iterator` or `impl Stream` the returned value itself isn't logged, instead <br>
+//! `(each = "LEVEL")` logs every item it yields and `(summary = "LEVEL")` logs the item count and the total time once it's exhausted. <br>
+//! (the leading log level is used for whichever of them isn't specified, and streams require the [`futures`] crate) <br>
+//!
 //! By default the macro uses the following formatting to print the message: <br>
 //! [`logfn`]: `("FUNCTION_NAME() => {:?}", return_val)` <br>
 //! [`logfn_inputs`]: `"FUNCTION_NAME(a: {:?}, b: {:?})", a, b)` <br>
 //! This can be easily changed using the `fmt` attribute: `#[logfn(LEVEL, fmt = "Important Result: {:}")` <br>
 //! which will accept format strings similar to [`println!`].
 //!
+//! The macros can be used on trait methods too. A method without a default body is split into a provided method with <br>
+//! the same signature, which is logged, and a required `<name>_impl` method that the implementations provide instead. <br>
+//!
+//! When calls interleave it's hard to tell which input log belongs to which output log, so both macros accept `call_id = true` <br>
+//! which appends `, call_id=N` to the message. The id is generated once per call (from a counter of that function) <br>
+//! and shared by [`logfn_inputs`] and [`logfn`] when both are used on the same function. <br>
+//!
+//! To get a readable call tree both macros accept `depth = "indent"` which indents the message by two spaces per level of <br>
+//! nested instrumented calls (or `depth = "number"` which prefixes it with `depth=N `). The depth is tracked per thread, <br>
+//! and per task for `async` functions, by the [`log-derive-runtime`] crate which must be a dependency of the crate using it. <br>
+//!
+//! The levels are fixed at compile time and filtered by `log::max_level()`, unless `registry = true` is used which lets the <br>
+//! maximum level be changed per function or module without recompiling, either by the `LOG_DERIVE` environment variable <br>
+//! (e.g. `LOG_DERIVE=my_crate::db::query=trace,my_crate::net=off`) or by `log_derive_runtime::registry::set_level`. <br>
+//! Like a `RUST_LOG` directive it only filters, every message keeps its level and `off` disables the function. <br>
+//!
+//! Hot functions can limit their logs with `sample = 0.01` (the probability of logging a message), `every = 1000` <br>
+//! (logs the first of every 1000 messages) or `max_per_sec = 10`. Each message of the function is counted separately, <br>
+//! and when `err = "LEVEL"` is specified errors are always logged. <br>
+//!
+//! The work done only for the messages, like measuring the time for `log_ts`, is skipped when their levels are disabled. <br>
+//!
+//! To remove the instrumentation entirely from some builds both macros accept `cfg = "PREDICATE"`, e.g. `cfg = "debug_assertions"` <br>
+//! or `cfg = "feature = \"trace\""`. The function is instrumented only when the predicate holds, and is emitted untouched otherwise. <br>
+//!
+//! Large values can be limited with `max_len = 256`, which truncates every logged value to 256 bytes followed by `…` and <br>
+//! its full length. The value is written straight into the message so it's never fully rendered in memory. <br>
+//!
+//! Binary values like `&[u8]`, `Vec<u8>` or `[u8; N]` can be rendered compactly, [`logfn_inputs`] accepts `hex(a, b)` and <br>
+//! `base64(c)` listing the inputs to render as hex or base64, and [`logfn`] accepts `hex_result` or `base64_result` for the output. <br>
+//!
+//! When the side effects tell more than the output, [`logfn`] accepts `after(self, buf)` which logs the state of the listed <br>
+//! (usually `&mut`) inputs after the body runs, alongside the output: `"FUNCTION_NAME() => {:?}, self={:?}, buf={:?}"`. <br>
+//!
+//! Similarly `diff(state)` snapshots a `&mut` input which is `Clone + Debug + PartialEq` before the body runs and logs <br>
+//! `state: unchanged`, or `state: BEFORE -> AFTER` if it changed. <br>
+//!
+//! For errors implementing [`std::error::Error`] (or boxed ones) `err_chain = true` logs the error followed by each of its <br>
+//! sources: `"invalid config, caused by: invalid digit found in string"`, and `backtrace = true` appends a backtrace <br>
+//! (which needs Rust 1.65). It's captured once the function returned the error, so it shows the call site of the <br>
+//! instrumented function and not where the error was created. <br>
+//!
+//! Errors can be logged at different levels by matching them against patterns (without guards), the first matching one is used: <br>
+//! `#[logfn(ok = "Info", err_levels(MyError::NotFound = "Debug", MyError::Timeout(_) = "Warn", _ = "Error"))]` <br>
+//! An error which matches none of them is logged at the `err` (or leading) level if there's one. <br>
+//!
+//! The level of the output can also be chosen by its value with `level_fn = "path::to::classify"`, where <br>
+//! `fn classify(output: &T) -> log::Level` is called with the output (or the `Ok` value of a `Result`). <br>
+//!
+//! An output which doesn't implement [`Debug`] can be logged with `display = true`, which formats it with `Display`, <br>
+//! or with `with = "path::to::render"`, where `fn render(output: &T) -> U` returns something formattable. <br>
+//!
+//! Similarly [`logfn_inputs`] accepts `maybe_debug = true`, which logs inputs that don't implement [`Debug`] by their type name, <br>
+//! so generic functions can be instrumented without adding `Debug` bounds. Note that a type parameter is always logged <br>
+//! by its type name, as its bounds are all that's known about it. <br>
+//!
+//! To tell which instantiation of a generic function ran, `type_names = true` appends the types of its type parameters <br>
+//! to the messages of [`logfn`] and [`logfn_inputs`]: `"process(item: 5), T=u8"`. <br>
+//!
+//! Only the interesting calls can be logged with a `when` condition, which can use the inputs, and in [`logfn`] also the `result`: <br>
+//! `#[logfn(Info, when = "result.len() > 100")]` or `#[logfn_inputs(Debug, when = "user_id == 0")]`. <br>
+//! Note that in [`logfn`] the condition sees the whole returned value, so for a `Result` it's e.g. `when = "result.is_err()"`. <br>
+//! The condition is only evaluated if the level of the message is enabled, except with `level_fn` where the condition is <br>
+//! always evaluated, as the level of the output is only chosen after it. <br>
+//!
+//! Retry loops can flood the logs with the same message, `dedupe = "10s"` suppresses a message repeating the last one of the <br>
+//! function within 10 seconds of its first occurrence, and logs `"MESSAGE (repeated N times)"` once a different one is logged. <br>
+//! The window is written in `ms`, `s`, `m` or `h`, and the state is kept by [`log-derive-runtime`]. <br>
+//!
+//! With `caller = true` the function gets `#[track_caller]`, and its messages end with the location it was called from: <br>
+//! `"get() => 2, caller=src/main.rs:10:5"`. It's not available for async functions, as they can't track their caller. <br>
+//!
+//! The file, module path and line of the records point at the name of the instrumented function, <br>
+//! or at the invocation of [`logclosure!`] and [`log_block!`], rather than at the code the macros generate. <br>
+//!
 //! [`logfn`]: ./attr.logfn.html
 //! [`logfn_inputs`]: ./attr.logfn_inputs.html
+//! [`logfn_all`]: ./attr.logfn_all.html
+//! [`logclosure!`]: ./macro.logclosure.html
+//! [`log_block!`]: ./macro.log_block.html
 //! [`log`]: https://docs.rs/log/latest/log/index.html
 //! [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
 //! [`println!`]: https://doc.rust-lang.org/stable/std/macro.println.html
 //! [`Debug`]: https://doc.rust-lang.org/std/fmt/trait.Debug.html
+//! [`futures`]: https://docs.rs/futures/latest/futures/index.html
+//! [`log-derive-runtime`]: https://docs.rs/log-derive-runtime
 //!
 //! ## Examples
 //! ```rust
@@ -87,68 +174,257 @@
 //! # #[derive(Debug)]
 //! # struct Person;
 //! # impl Person {fn ping(&self) -> Response {Response::Pong}fn is_awake(&self) -> bool {true}}
+//! #[logfn(Debug, each = "Trace", summary = "Info")]
+//! fn even_numbers(limit: u32) -> impl Iterator<Item = u32> {
+//!     (0..limit).filter(|n| n % 2 == 0)
+//! }
+//!
 //! # use std::time::Duration;
 //! ```
 //!
 //!
 extern crate proc_macro;
 extern crate syn;
-use darling::{Error, FromMeta};
-use proc_macro2::TokenStream;
-use quote::{quote, ToTokens};
+use darling::{util::PathList, Error, FromMeta};
+use proc_macro2::{Delimiter, Span, TokenStream, TokenTree};
+use quote::{quote, quote_spanned, ToTokens};
 
+use std::iter;
+use syn::parse::{Parse, ParseStream, Parser};
 use syn::punctuated::Punctuated;
 use syn::{
-    parse_macro_input, spanned::Spanned, token, AttributeArgs, Expr, ExprAsync, ExprAwait, ExprBlock, ExprCall, ExprClosure,
-    ExprParen, FnArg, Ident, ItemFn, Meta, NestedMeta, Pat, Result, ReturnType, Stmt, Type, TypePath,
+    parse_macro_input, spanned::Spanned, token, Attribute, AttributeArgs, Block, Expr, ExprAsync, ExprAwait, ExprBlock, ExprCall,
+    ExprClosure, ExprParen, FnArg, GenericParam, Ident, ImplItem, ImplItemMethod, Item, ItemFn, ItemMod, LitStr, Local, Meta,
+    NestedMeta, Pat, Result, ReturnType, Signature, Stmt, Token, TraitItem, TraitItemMethod, Type, TypeParamBound, TypePath,
+    Visibility,
 };
 
 struct FormattedAttributes {
     ok_expr: TokenStream,
     err_expr: TokenStream,
+    each_expr: TokenStream,
+    summary_expr: TokenStream,
     log_ts: bool,
+    /// Whether the messages use inputs, so the body can't move them.
+    borrows_inputs: bool,
+    /// The statements taking the `diff` snapshots before the body runs.
+    snapshots: TokenStream,
+    /// Whether the `ok` or `err` messages are enabled, for the `ts` measurement and the `diff` snapshots.
+    output_enabled: TokenStream,
+    /// Whether the `summary` message is enabled, for its `ts` measurement.
+    summary_enabled: TokenStream,
+    /// The statement evaluating the `when` condition after the body returns.
+    when: TokenStream,
+    /// The static holding the `dedupe` state of the messages.
+    dedupe_site: TokenStream,
+    call_id: bool,
+    depth: bool,
+    caller: bool,
     contained_ok_or_err: bool,
+    contained_each_or_summary: bool,
 }
 
 impl FormattedAttributes {
-    pub fn parse_attributes(attr: &[NestedMeta], fmt_default: String) -> darling::Result<Self> {
-        OutputOptions::from_list(attr).map(|opts| Self::get_ok_err_streams(opts, fmt_default))
+    pub fn parse_attributes(attr: &[NestedMeta], err_levels: &[ErrLevel], sig: &Signature) -> darling::Result<Self> {
+        let att = OutputOptions::with_err_levels(attr, err_levels)?;
+        let inputs: Vec<_> = sig
+            .inputs
+            .iter()
+            .filter_map(|input| match input {
+                FnArg::Receiver(receiver) => Some(Ident::from(receiver.self_token)),
+                FnArg::Typed(pat_type) => match pat_type.pat.as_ref() {
+                    Pat::Ident(pat) => Some(pat.ident.clone()),
+                    _ => None,
+                },
+            })
+            .collect();
+        att.check_inputs(Some(&inputs))?;
+        let type_params: Vec<_> = sig.generics.type_params().map(|param| param.ident.clone()).collect();
+        let fn_name = sig.ident.to_string();
+        let default_fmt = format!("{}() => {{:?}}", fn_name);
+        Ok(Self::get_ok_err_streams(att, &fn_name, default_fmt, &type_params, sig.ident.span()))
     }
 
-    fn get_ok_err_streams(att: OutputOptions, fmt_default: String) -> Self {
+    /// The records point at the `location`, the name of the function or the invocation of the macro.
+    /// The `default_fmt` is the message without `fmt`, `display` still applies to it.
+    fn get_ok_err_streams(att: OutputOptions, fn_name: &str, default_fmt: String, type_params: &[Ident], location: Span) -> Self {
         let contained_ok_or_err = att.contains_ok_or_err();
+        let contained_each_or_summary = att.contains_each_or_summary();
         let log_ts = att.log_ts();
+        let call_id = att.call_id();
+        let depth = att.depth();
         let ok_log = att.ok_log();
         let err_log = att.err_log();
-        let mut fmt = att.fmt().unwrap_or(fmt_default);
+        let explicit_err = att.named.err.is_some() || !att.err_levels.is_empty();
+        let when_ident = shared_local_ident(WHEN);
+        let gate_when = |log: TokenStream| match att.when() {
+            Some(_) => quote!(if #when_ident { #log }),
+            None => log,
+        };
+        let decorations = Decorations::new(
+            depth,
+            call_id,
+            Some(fn_name).filter(|_| att.registry()),
+            att.sampling(),
+            att.max_len(),
+            att.named.dedupe,
+            location,
+        )
+        .with_caller(att.caller());
+        let (each_expr, summary_expr) = Self::get_each_summary_streams(&att, fn_name, &decorations);
+        let mut fmt = att.fmt().unwrap_or(default_fmt);
+        let output_enabled = match att.level_fn() {
+            // The level is only known once the body returns.
+            Some(_) => quote!(true),
+            None => {
+                decorations.enabled(ok_log.into_iter().chain(err_log).chain(att.err_levels.iter().map(|err_level| &err_level.level)))
+            }
+        };
+        let mut extra_args = Vec::new();
+        for input in att.after() {
+            fmt += &format!(", {}={{:?}}", input.to_token_stream());
+            extra_args.push(input.to_token_stream());
+        }
+        let mut snapshots = TokenStream::new();
+        for input in att.diff() {
+            let name = input.to_token_stream().to_string();
+            let before = Ident::new(&format!("{}before_{}", SHARED_LOCAL_PREFIX, name), proc_macro2::Span::call_site());
+            snapshots.extend(quote! {
+                let #before = if #output_enabled { Some(std::clone::Clone::clone(&*#input)) } else { None };
+            });
+            fmt += &format!(", {}: {{}}", name);
+            extra_args.push(quote!(log_derive_runtime::diff::Diff::new(#before.as_ref(), &*#input)));
+        }
+        if att.type_names() {
+            let (type_fmt, type_args) = type_names(type_params);
+            fmt += &type_fmt;
+            extra_args.extend(type_args);
+        }
         if log_ts {
-            fmt += ", ts={:#?}"
+            fmt += ", ts={:#?}";
+            extra_args.push(quote!(ts));
+        }
+
+        // With `display` the default message formats the output with `Display`, errors are still formatted with `Debug`.
+        let ok_fmt = match att.fmt() {
+            None if att.display() => fmt.replacen("{:?}", "{}", 1),
+            _ => fmt.clone(),
+        };
+        let result = match (att.result_format(), att.with()) {
+            (Some(format), _) => format.wrap(quote!(result)),
+            (None, Some(with)) => quote!(#with(&result)),
+            (None, None) => quote!(result),
+        };
+        let ok_expr = match (att.level_fn(), ok_log) {
+            (Some(level_fn), _) => {
+                let level = shared_local_ident(RUNTIME_LEVEL);
+                let log = decorations.log(&level.to_token_stream(), &ok_fmt, iter::once(result).chain(extra_args.clone()));
+                quote! {{
+                    let #level: log::Level = #level_fn(&result);
+                    #log
+                }}
+            }
+            (None, Some(loglevel)) => {
+                let log_token = get_logger_token(loglevel);
+                decorations.log(&log_token, &ok_fmt, iter::once(result).chain(extra_args.clone()))
+            }
+            (None, None) => quote! {()},
         };
+        let ok_expr = if att.level_fn().is_some() || ok_log.is_some() { gate_when(ok_expr) } else { ok_expr };
 
-        let ok_expr = match ok_log {
-            Some(loglevel) => {
-                let log_token = get_logger_token(&loglevel);
-                if log_ts {
-                    quote! {log::log!(#log_token, #fmt, result, ts); }
-                } else {
-                    quote! {log::log!(#log_token, #fmt, result); }
+        let log_err = |loglevel: &Ident| {
+            let log_token = get_logger_token(loglevel);
+            let err = if att.err_chain() {
+                quote! {{
+                    use log_derive_runtime::error::{BoxedErrorChain as _, ErrorChain as _};
+                    err.error_chain()
+                }}
+            } else {
+                quote!(err)
+            };
+            let mut fmt = fmt.clone();
+            let mut args: Vec<_> = iter::once(err).chain(extra_args.iter().cloned()).collect();
+            if att.backtrace() {
+                // An argument of `log!` is only evaluated if the message is enabled, so it's captured only if needed.
+                // It's captured after the body returned, so it ends at the instrumented function rather than inside it.
+                fmt += ", backtrace:\n{}";
+                args.push(quote!(std::backtrace::Backtrace::force_capture()));
+            }
+            // Errors are rare and important, so an explicit `err` level isn't limited by the sampling.
+            gate_when(if explicit_err {
+                decorations.log_always(&log_token, &fmt, args)
+            } else {
+                decorations.log(&log_token, &fmt, args)
+            })
+        };
+        let err_expr = if att.err_levels.is_empty() {
+            err_log.map_or_else(|| quote! {()}, log_err)
+        } else {
+            let default_err_expr = err_log.map(log_err);
+            let arms = att.err_levels.iter().map(|ErrLevel { pat, level }| {
+                let log = log_err(level);
+                quote!(#pat => { #log })
+            });
+            quote! {
+                match &err {
+                    #(#arms)*
+                    #[allow(unreachable_patterns)]
+                    _ => { #default_err_expr }
                 }
             }
+        };
+        let borrows_inputs = !att.after().is_empty() || !att.diff().is_empty() || att.when().is_some();
+        let summary_enabled = decorations.enabled(att.summary_log());
+        // The condition is only evaluated if the output can be logged, with `level_fn` the level isn't known yet.
+        let when = match att.when() {
+            Some(condition) if att.level_fn().is_some() => quote!(let #when_ident: bool = #condition;),
+            Some(condition) => quote!(let #when_ident: bool = (#output_enabled) && (#condition);),
+            None => TokenStream::new(),
+        };
+        FormattedAttributes {
+            ok_expr,
+            err_expr,
+            each_expr,
+            summary_expr,
+            log_ts,
+            borrows_inputs,
+            snapshots,
+            output_enabled,
+            summary_enabled,
+            when,
+            dedupe_site: decorations.dedupe_site(),
+            call_id,
+            depth: depth.is_some(),
+            caller: att.caller(),
+            contained_ok_or_err,
+            contained_each_or_summary,
+        }
+    }
+
+    /// The `each` log uses `item` and `fmt`, the `summary` log uses `count` and the optional `instant` from [`generate_sequence`].
+    fn get_each_summary_streams(att: &OutputOptions, fn_name: &str, decorations: &Decorations) -> (TokenStream, TokenStream) {
+        let each_expr = match att.each_log() {
+            Some(loglevel) => {
+                let log_token = get_logger_token(loglevel);
+                let fmt = att.fmt().unwrap_or_else(|| format!("{}() yielded {{:?}}", fn_name));
+                decorations.log(&log_token, &fmt, vec![quote!(item)])
+            }
             None => quote! {()},
         };
 
-        let err_expr = match err_log {
+        let summary_expr = match att.summary_log() {
             Some(loglevel) => {
-                let log_token = get_logger_token(&loglevel);
-                if log_ts {
-                    quote! {log::log!(#log_token, #fmt, err, ts); }
-                } else {
-                    quote! {log::log!(#log_token, #fmt, err); }
-                }
+                let log_token = get_logger_token(loglevel);
+                let fmt = format!("{}() finished after {{}} items, ts={{:#?}}", fn_name);
+                decorations.log(
+                    &log_token,
+                    &fmt,
+                    vec![quote!(count), quote!(instant.map_or_else(std::time::Duration::default, |instant| instant.elapsed()))],
+                )
             }
             None => quote! {()},
         };
-        FormattedAttributes { ok_expr, err_expr, log_ts, contained_ok_or_err }
+        (each_expr, summary_expr)
     }
 }
 
@@ -159,23 +435,185 @@ struct OutputNamedOptions {
     err: Option<Ident>,
     fmt: Option<String>,
     log_ts: Option<bool>,
+    each: Option<Ident>,
+    summary: Option<Ident>,
+    call_id: Option<bool>,
+    depth: Option<DepthStyle>,
+    registry: Option<bool>,
+    sample: Option<f64>,
+    every: Option<u64>,
+    max_per_sec: Option<u64>,
+    max_len: Option<usize>,
+    hex_result: Option<bool>,
+    base64_result: Option<bool>,
+    after: PathList,
+    diff: PathList,
+    err_chain: Option<bool>,
+    backtrace: Option<bool>,
+    level_fn: Option<syn::Path>,
+    when: Option<Condition>,
+    dedupe: Option<DedupeWindow>,
+    display: Option<bool>,
+    with: Option<syn::Path>,
+    type_names: Option<bool>,
+    caller: Option<bool>,
 }
 
 struct OutputOptions {
     /// The log level specified as the first word in the attribute.
     leading_level: Option<Ident>,
     named: OutputNamedOptions,
+    /// Parsed separately by [`take_err_levels`], as the patterns aren't valid in a `Meta`.
+    err_levels: Vec<ErrLevel>,
 }
 
-struct InputOptions {
+/// A `PATTERN = "LEVEL"` item of `err_levels(...)`.
+#[derive(Clone)]
+struct ErrLevel {
+    pat: Pat,
     level: Ident,
+}
+
+impl Parse for ErrLevel {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let pat = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let level: LitStr = input.parse()?;
+        let level = level.parse().map_err(|_| syn::Error::new(level.span(), "expected a log level"))?;
+        Ok(ErrLevel { pat, level })
+    }
+}
+
+/// Parses an option of [`logclosure!`] or [`log_block!`] and the comma after it, `err_levels(...)` is parsed separately
+/// like [`take_err_levels`] does for the attributes.
+fn parse_option(input: ParseStream, attr: &mut Vec<NestedMeta>, err_levels: &mut Vec<ErrLevel>) -> Result<()> {
+    let is_err_levels = input.peek(Ident) && input.peek2(token::Paren) && input.fork().parse::<Ident>()? == "err_levels";
+    if is_err_levels {
+        input.parse::<Ident>()?;
+        let content;
+        syn::parenthesized!(content in input);
+        err_levels.extend(Punctuated::<ErrLevel, Token![,]>::parse_terminated(&content)?);
+    } else {
+        attr.push(input.parse()?);
+    }
+    input.parse::<Token![,]>()?;
+    Ok(())
+}
+
+/// Takes `err_levels(...)` out of the attribute, so the rest of it can be parsed as `AttributeArgs`.
+fn take_err_levels(attr: TokenStream) -> Result<(TokenStream, Vec<ErrLevel>)> {
+    let mut tokens = attr.into_iter().peekable();
+    let mut rest = TokenStream::new();
+    let mut err_levels = Vec::new();
+    while let Some(token) = tokens.next() {
+        match (&token, tokens.peek()) {
+            (TokenTree::Ident(ident), Some(TokenTree::Group(group)))
+                if ident == "err_levels" && group.delimiter() == Delimiter::Parenthesis =>
+            {
+                let items = Punctuated::<ErrLevel, Token![,]>::parse_terminated.parse2(group.stream())?;
+                err_levels.extend(items);
+                tokens.next();
+                // The comma after it.
+                if let Some(TokenTree::Punct(punct)) = tokens.peek() {
+                    if punct.as_char() == ',' {
+                        tokens.next();
+                    }
+                }
+            }
+            _ => rest.extend(iter::once(token)),
+        }
+    }
+    Ok((rest, err_levels))
+}
+
+#[derive(Default, FromMeta)]
+#[darling(default)]
+struct InputNamedOptions {
     fmt: Option<String>,
+    call_id: Option<bool>,
+    depth: Option<DepthStyle>,
+    registry: Option<bool>,
+    sample: Option<f64>,
+    every: Option<u64>,
+    max_per_sec: Option<u64>,
+    max_len: Option<usize>,
+    hex: PathList,
+    base64: PathList,
+    when: Option<Condition>,
+    dedupe: Option<DedupeWindow>,
+    maybe_debug: Option<bool>,
+    type_names: Option<bool>,
+    caller: Option<bool>,
+}
+
+struct InputOptions {
+    level: Ident,
+    named: InputNamedOptions,
+}
+
+impl InputOptions {
+    pub fn fmt(&self) -> Option<String> {
+        self.named.fmt.clone()
+    }
+
+    pub fn call_id(&self) -> bool {
+        self.named.call_id.unwrap_or(false)
+    }
+
+    pub fn depth(&self) -> Option<DepthStyle> {
+        self.named.depth
+    }
+
+    pub fn registry(&self) -> bool {
+        self.named.registry.unwrap_or(false)
+    }
+
+    pub fn sampling(&self) -> Sampling {
+        Sampling { sample: self.named.sample, every: self.named.every, max_per_sec: self.named.max_per_sec }
+    }
+
+    pub fn max_len(&self) -> Option<usize> {
+        self.named.max_len
+    }
+
+    pub fn when(&self) -> Option<&Expr> {
+        self.named.when.as_ref().map(|condition| &condition.0)
+    }
+
+    pub fn type_names(&self) -> bool {
+        self.named.type_names.unwrap_or(false)
+    }
+
+    pub fn caller(&self) -> bool {
+        self.named.caller.unwrap_or(false)
+    }
+}
+
+impl InputOptions {
+    /// The expressions logging each input, the ones listed in `hex(...)` or `base64(...)` are wrapped in their formatter,
+    /// and with `maybe_debug` the others are rendered by their type name if they don't implement `Debug`.
+    pub fn input_args(&self, inputs: &[Ident]) -> syn::Result<Vec<TokenStream>> {
+        let formats = [(ByteFormat::Hex, &self.named.hex), (ByteFormat::Base64, &self.named.base64)];
+        for path in formats.iter().flat_map(|(_, paths)| paths.iter()) {
+            if !inputs.iter().any(|input| path.is_ident(input)) {
+                return Err(syn::Error::new_spanned(path, "there's no input with this name"));
+            }
+        }
+        let args = inputs.iter().map(|input| match formats.iter().find(|(_, paths)| paths.iter().any(|path| path.is_ident(input))) {
+            Some((format, _)) => format.wrap(input.to_token_stream()),
+            None if self.named.maybe_debug.unwrap_or(false) => quote! {{
+                use log_derive_runtime::maybe_debug::{ViaDebug as _, ViaTypeName as _};
+                (&log_derive_runtime::maybe_debug::Wrap(&#input)).maybe_debug()
+            }},
+            None => input.to_token_stream(),
+        });
+        Ok(args.collect())
+    }
 }
 
 impl FromMeta for InputOptions {
     fn from_list(items: &[NestedMeta]) -> darling::Result<Self> {
         let level;
-        let mut fmt = None;
         if items.is_empty() {
             return Err(Error::too_few_items(1));
         }
@@ -195,57 +633,310 @@ impl FromMeta for InputOptions {
             NestedMeta::Lit(lit) => return Err(Error::unexpected_lit_type(lit)),
         }
 
-        if items.len() > 1 {
-            fmt = String::from_nested_meta(&items[1]).ok();
-        }
+        let named = InputNamedOptions::from_list(&items[1..])?;
+        let options = InputOptions { level, named };
+        options.sampling().validate()?;
 
-        Ok(InputOptions { level, fmt })
+        Ok(options)
     }
 }
 
 impl OutputOptions {
     pub fn ok_log(&self) -> Option<&Ident> {
-        self.named.ok.as_ref().or_else(|| self.leading_level.as_ref())
+        self.named.ok.as_ref().or(self.leading_level.as_ref())
     }
 
     pub fn err_log(&self) -> Option<&Ident> {
-        self.named.err.as_ref().or_else(|| self.leading_level.as_ref())
+        self.named.err.as_ref().or(self.leading_level.as_ref())
     }
 
     pub fn contains_ok_or_err(&self) -> bool {
-        self.named.ok.is_some() || self.named.err.is_some()
+        self.named.ok.is_some() || self.named.err.is_some() || !self.err_levels.is_empty()
+    }
+
+    pub fn each_log(&self) -> Option<&Ident> {
+        self.named.each.as_ref().or(self.leading_level.as_ref())
+    }
+
+    pub fn summary_log(&self) -> Option<&Ident> {
+        self.named.summary.as_ref().or(self.leading_level.as_ref())
+    }
+
+    pub fn contains_each_or_summary(&self) -> bool {
+        self.named.each.is_some() || self.named.summary.is_some()
     }
 
     pub fn log_ts(&self) -> bool {
         self.named.log_ts.unwrap_or(false)
     }
 
+    pub fn call_id(&self) -> bool {
+        self.named.call_id.unwrap_or(false)
+    }
+
+    pub fn depth(&self) -> Option<DepthStyle> {
+        self.named.depth
+    }
+
+    pub fn registry(&self) -> bool {
+        self.named.registry.unwrap_or(false)
+    }
+
+    pub fn sampling(&self) -> Sampling {
+        Sampling { sample: self.named.sample, every: self.named.every, max_per_sec: self.named.max_per_sec }
+    }
+
+    pub fn max_len(&self) -> Option<usize> {
+        self.named.max_len
+    }
+
     pub fn fmt(&self) -> Option<String> {
         self.named.fmt.clone()
     }
+
+    /// The mutable inputs to log after the body runs.
+    pub fn after(&self) -> &[syn::Path] {
+        &self.named.after
+    }
+
+    /// The function choosing the level of the output by its value.
+    pub fn level_fn(&self) -> Option<&syn::Path> {
+        self.named.level_fn.as_ref()
+    }
+
+    pub fn err_chain(&self) -> bool {
+        self.named.err_chain.unwrap_or(false)
+    }
+
+    pub fn backtrace(&self) -> bool {
+        self.named.backtrace.unwrap_or(false)
+    }
+
+    /// The condition deciding whether the output is logged.
+    pub fn when(&self) -> Option<&Expr> {
+        self.named.when.as_ref().map(|condition| &condition.0)
+    }
+
+    /// The mutable inputs whose changes are logged.
+    pub fn diff(&self) -> &[syn::Path] {
+        &self.named.diff
+    }
+
+    pub fn display(&self) -> bool {
+        self.named.display.unwrap_or(false)
+    }
+
+    pub fn type_names(&self) -> bool {
+        self.named.type_names.unwrap_or(false)
+    }
+
+    pub fn caller(&self) -> bool {
+        self.named.caller.unwrap_or(false)
+    }
+
+    /// The function converting the output to the value that's logged.
+    pub fn with(&self) -> Option<&syn::Path> {
+        self.named.with.as_ref()
+    }
+
+    pub fn result_format(&self) -> Option<ByteFormat> {
+        if self.named.hex_result.unwrap_or(false) {
+            Some(ByteFormat::Hex)
+        } else if self.named.base64_result.unwrap_or(false) {
+            Some(ByteFormat::Base64)
+        } else {
+            None
+        }
+    }
+}
+
+impl OutputOptions {
+    /// Parses the options with the separately parsed `err_levels(...)`, which is enough on its own.
+    fn with_err_levels(attr: &[NestedMeta], err_levels: &[ErrLevel]) -> darling::Result<Self> {
+        let options = if attr.is_empty() && !err_levels.is_empty() {
+            OutputOptions { leading_level: None, named: OutputNamedOptions::default(), err_levels: Vec::new() }
+        } else {
+            OutputOptions::from_list(attr)?
+        };
+        Ok(OutputOptions { err_levels: err_levels.to_vec(), ..options })
+    }
+
+    /// Checks that the `after` and `diff` entries are single names, and if the `inputs` are known that they name one of them.
+    fn check_inputs(&self, inputs: Option<&[Ident]>) -> darling::Result<()> {
+        for path in self.after().iter().chain(self.diff()) {
+            match (path.get_ident(), inputs) {
+                (None, _) => return Err(Error::custom("expected the name of an input").with_span(path)),
+                (Some(ident), Some(inputs)) if !inputs.contains(ident) => {
+                    return Err(Error::custom("there's no input with this name").with_span(path))
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
 }
 
 impl FromMeta for OutputOptions {
     fn from_list(items: &[NestedMeta]) -> darling::Result<Self> {
-        if items.is_empty() {
-            return Err(darling::Error::too_few_items(1));
+        let (leading_level, items) = split_leading_level(items)?;
+        let named = OutputNamedOptions::from_list(items)?;
+        let options = OutputOptions { leading_level, named, err_levels: Vec::new() };
+        options.sampling().validate()?;
+        if options.named.hex_result.unwrap_or(false) && options.named.base64_result.unwrap_or(false) {
+            return Err(Error::custom("hex_result and base64_result can't be used together"));
+        }
+        if options.named.with.is_some() && options.result_format().is_some() {
+            return Err(Error::custom("the output is already formatted by hex_result or base64_result").at("with"));
+        }
+        if options.named.level_fn.is_some() && options.registry() {
+            return Err(Error::custom("the registry requires a fixed level, so it can't be used with level_fn"));
+        }
+        if (!options.named.after.is_empty() || !options.named.diff.is_empty()) && options.contains_each_or_summary() {
+            return Err(Error::custom("the items are logged lazily, so there's no point to log the inputs after them"));
+        }
+        if options.named.when.is_some() && options.contains_each_or_summary() {
+            return Err(Error::custom("the items are logged lazily, so there's no result for when to check").at("when"));
         }
 
-        let mut leading_level = None;
+        Ok(options)
+    }
+}
 
-        if let NestedMeta::Meta(first) = &items[0] {
-            if let Meta::Path(path) = first {
-                leading_level = path.get_ident().cloned();
-            }
+/// The expression of `when = "..."`, parsed while expanding so its errors point at the attribute.
+struct Condition(Expr);
+
+impl FromMeta for Condition {
+    fn from_value(value: &syn::Lit) -> darling::Result<Self> {
+        match value {
+            syn::Lit::Str(lit) => lit.parse().map(Condition).map_err(|err| Error::custom(err).with_span(lit)),
+            _ => Err(Error::unexpected_lit_type(value)),
+        }
+    }
+}
+
+/// The window of `dedupe = "..."` in milliseconds, written as a number followed by `ms`, `s`, `m` or `h`.
+#[derive(Clone, Copy)]
+struct DedupeWindow(u64);
+
+impl FromMeta for DedupeWindow {
+    fn from_string(value: &str) -> darling::Result<Self> {
+        let split = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+        let (number, unit) = value.split_at(split);
+        let millis = match unit {
+            "ms" => 1,
+            "s" => 1000,
+            "m" => 60 * 1000,
+            "h" => 60 * 60 * 1000,
+            _ => return Err(Error::custom("expected a duration like \"500ms\", \"10s\", \"5m\" or \"1h\"")),
+        };
+        match number.parse::<u64>().ok().and_then(|number| number.checked_mul(millis)) {
+            Some(0) => Err(Error::custom("the window must be longer than zero")),
+            Some(window) => Ok(DedupeWindow(window)),
+            None => Err(Error::unknown_value(value)),
         }
+    }
+}
 
-        let named =
-            if leading_level.is_some() { OutputNamedOptions::from_list(&items[1..])? } else { OutputNamedOptions::from_list(items)? };
+/// Compact renderings of binary values, implemented by `log_derive_runtime::bytes`.
+#[derive(Clone, Copy)]
+enum ByteFormat {
+    Hex,
+    Base64,
+}
 
-        Ok(OutputOptions { leading_level, named })
+impl ByteFormat {
+    fn wrap(self, value: TokenStream) -> TokenStream {
+        match self {
+            ByteFormat::Hex => quote!(log_derive_runtime::bytes::Hex(&#value)),
+            ByteFormat::Base64 => quote!(log_derive_runtime::bytes::Base64(&#value)),
+        }
     }
 }
 
+/// Limits how often a message is logged, every message of the function is counted separately.
+#[derive(Clone, Copy, Default)]
+struct Sampling {
+    /// The probability of logging each message.
+    sample: Option<f64>,
+    /// Logs only the first of every `every` messages.
+    every: Option<u64>,
+    /// Logs at most `max_per_sec` messages in each second.
+    max_per_sec: Option<u64>,
+}
+
+impl Sampling {
+    fn validate(&self) -> darling::Result<()> {
+        if let Some(false) = self.sample.map(|sample| sample > 0.0 && sample <= 1.0) {
+            return Err(Error::custom("must be in the range (0, 1]").at("sample"));
+        }
+        if self.every == Some(0) {
+            return Err(Error::custom("must be at least 1").at("every"));
+        }
+        Ok(())
+    }
+
+    /// The condition gating the message, the counters are statics so it's cheap and shared by all threads.
+    fn gate(&self) -> Option<TokenStream> {
+        let mut conditions = Vec::new();
+        if let Some(every) = self.every.filter(|every| *every > 1) {
+            conditions.push(quote! {{
+                static COUNT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+                COUNT.fetch_add(1, std::sync::atomic::Ordering::Relaxed) % #every == 0
+            }});
+        }
+        if let Some(sample) = self.sample.filter(|sample| *sample < 1.0) {
+            // A splitmix64 sequence, good enough to pick messages and doesn't need a dependency.
+            let threshold = (sample * u64::MAX as f64) as u64;
+            conditions.push(quote! {{
+                static STATE: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+                let mut x = STATE
+                    .fetch_add(0x9E37_79B9_7F4A_7C15, std::sync::atomic::Ordering::Relaxed)
+                    .wrapping_add(0x9E37_79B9_7F4A_7C15);
+                x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+                x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+                (x ^ (x >> 31)) <= #threshold
+            }});
+        }
+        if let Some(max_per_sec) = self.max_per_sec {
+            conditions.push(quote! {{
+                static WINDOW: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+                static COUNT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+                let now = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map_or(0, |since| since.as_secs());
+                let window = WINDOW.load(std::sync::atomic::Ordering::Relaxed);
+                if window != now
+                    && WINDOW
+                        .compare_exchange(window, now, std::sync::atomic::Ordering::Relaxed, std::sync::atomic::Ordering::Relaxed)
+                        .is_ok()
+                {
+                    COUNT.store(0, std::sync::atomic::Ordering::Relaxed);
+                }
+                COUNT.fetch_add(1, std::sync::atomic::Ordering::Relaxed) < #max_per_sec
+            }});
+        }
+        if conditions.is_empty() {
+            None
+        } else {
+            Some(quote!(#(#conditions)&&*))
+        }
+    }
+}
+
+/// Splits the optional log level at the start of the attribute from the named options after it.
+fn split_leading_level(items: &[NestedMeta]) -> darling::Result<(Option<Ident>, &[NestedMeta])> {
+    if items.is_empty() {
+        return Err(darling::Error::too_few_items(1));
+    }
+
+    if let NestedMeta::Meta(Meta::Path(path)) = &items[0] {
+        if let Some(ident) = path.get_ident() {
+            return Ok((Some(ident.clone()), &items[1..]));
+        }
+    }
+    Ok((None, items))
+}
+
 /// Check if a return type is some form of `Result`. This assumes that all types named `Result`
 /// are in fact results, but is resilient to the possibility of `Result` types being referenced
 /// from specific modules.
@@ -268,6 +959,300 @@ fn check_if_return_result(f: &ItemFn) -> bool {
     false
 }
 
+/// The kinds of lazy sequences whose items can be logged with `each` and `summary`.
+#[derive(Clone, Copy)]
+enum SequenceKind {
+    Iterator,
+    Stream,
+}
+
+/// Check if the function returns `impl Iterator` or `impl Stream`, the bounds are matched by their last segment
+/// so `impl std::iter::Iterator<Item = T>` and `impl futures::Stream<Item = T> + Send` are both recognized.
+fn check_if_return_sequence(f: &ItemFn) -> Option<SequenceKind> {
+    if let ReturnType::Type(_, t) = &f.sig.output {
+        if let Type::ImplTrait(impl_trait) = t.as_ref() {
+            return impl_trait.bounds.iter().find_map(|bound| match bound {
+                TypeParamBound::Trait(bound) => match bound.path.segments.last() {
+                    Some(segment) if segment.ident == "Iterator" => Some(SequenceKind::Iterator),
+                    Some(segment) if segment.ident == "Stream" => Some(SequenceKind::Stream),
+                    _ => None,
+                },
+                TypeParamBound::Lifetime(_) => None,
+            });
+        }
+    }
+
+    None
+}
+
+/// How the nesting depth of the call is rendered at the start of the message.
+#[derive(Clone, Copy, FromMeta)]
+#[darling(rename_all = "lowercase")]
+enum DepthStyle {
+    Indent,
+    Number,
+}
+
+/// The parts shared by every message of a macro, the `depth` is prepended to the message and the `call_id` is appended.
+/// With `registry` every message is filtered at runtime by the override of its function,
+/// with `sampling` the messages are gated by their counters, and with `max_len` the logged values are truncated.
+/// With `dedupe` repeated messages are collapsed by the runtime, so the message is formatted before it's logged,
+/// and every message of the function shares the state declared by [`Decorations::dedupe_site`].
+/// The records are built explicitly, so their line is the one of the `location` rather than of the generated code.
+struct Decorations {
+    prefix_fmt: String,
+    prefix_args: Vec<TokenStream>,
+    suffix_fmt: String,
+    suffix_args: Vec<TokenStream>,
+    registry: Option<String>,
+    sampling: Sampling,
+    max_len: Option<usize>,
+    dedupe: Option<DedupeWindow>,
+    location: Span,
+}
+
+impl Decorations {
+    fn new(
+        depth: Option<DepthStyle>,
+        call_id: bool,
+        registry: Option<&str>,
+        sampling: Sampling,
+        max_len: Option<usize>,
+        dedupe: Option<DedupeWindow>,
+        location: Span,
+    ) -> Self {
+        let mut decorations = Decorations {
+            prefix_fmt: String::new(),
+            prefix_args: Vec::new(),
+            suffix_fmt: String::new(),
+            suffix_args: Vec::new(),
+            registry: registry.map(String::from),
+            sampling,
+            max_len,
+            dedupe,
+            location,
+        };
+        if let Some(style) = depth {
+            let depth = shared_local_ident(DEPTH);
+            decorations.prefix_fmt.push_str("{}");
+            decorations.prefix_args.push(match style {
+                DepthStyle::Indent => quote!(log_derive_runtime::depth::Indent(#depth)),
+                DepthStyle::Number => quote!(log_derive_runtime::depth::Number(#depth)),
+            });
+        }
+        if call_id {
+            decorations.suffix_fmt.push_str(", call_id={}");
+            decorations.suffix_args.push(shared_local_ident(CALL_ID).into_token_stream());
+        }
+        decorations
+    }
+
+    /// Appends the location of the caller, captured by [`add_shared_locals`] in a `#[track_caller]` function.
+    fn with_caller(mut self, caller: bool) -> Self {
+        if caller {
+            self.suffix_fmt.push_str(", caller={}");
+            self.suffix_args.push(shared_local_ident(CALLER).into_token_stream());
+        }
+        self
+    }
+
+    fn log(&self, level: &TokenStream, fmt: &str, args: impl IntoIterator<Item = TokenStream>) -> TokenStream {
+        self.log_gated(level, fmt, args, self.sampling.gate())
+    }
+
+    /// Logs the message without the `sampling` limits.
+    fn log_always(&self, level: &TokenStream, fmt: &str, args: impl IntoIterator<Item = TokenStream>) -> TokenStream {
+        self.log_gated(level, fmt, args, None)
+    }
+
+    /// The `gate` is only evaluated if the level is enabled, so disabled messages don't advance its counters.
+    fn log_gated(
+        &self,
+        level: &TokenStream,
+        fmt: &str,
+        args: impl IntoIterator<Item = TokenStream>,
+        gate: Option<TokenStream>,
+    ) -> TokenStream {
+        let fmt = format!("{}{}{}", self.prefix_fmt, fmt, self.suffix_fmt);
+        let args: Vec<_> = match self.max_len {
+            Some(max_len) => {
+                args.into_iter().map(|arg| quote!(log_derive_runtime::truncate::Truncate::new(&#arg, #max_len))).collect()
+            }
+            None => args.into_iter().collect(),
+        };
+        let args = self.prefix_args.iter().cloned().chain(args).chain(self.suffix_args.iter().cloned());
+        let log = match self.dedupe {
+            Some(_) => {
+                let site = Ident::new(DEDUPE_SITE, proc_macro2::Span::call_site());
+                let record = self.record(&quote!(level), "{}", iter::once(quote!(msg)));
+                quote! { #site.log(#level, format!(#fmt, #(#args),*), |level, msg| #record); }
+            }
+            None => self.record(level, &fmt, args),
+        };
+        let enabled = match &self.registry {
+            Some(name) => self.site_enabled(name, level),
+            None => quote!(log::log_enabled!(#level)),
+        };
+        match (gate, &self.dedupe, &self.registry) {
+            (Some(gate), _, _) => quote! { if #enabled && #gate { #log } },
+            // Formatting the message isn't free, unlike `log!` which checks the level first.
+            (None, Some(_), _) | (None, None, Some(_)) => quote! { if #enabled { #log } },
+            (None, None, None) => log,
+        }
+    }
+
+    /// Logs the record like `log!` does, but with the line of the `location`.
+    fn record(&self, level: &TokenStream, fmt: &str, args: impl IntoIterator<Item = TokenStream>) -> TokenStream {
+        let args = args.into_iter();
+        let line = quote_spanned!(self.location=> std::line!());
+        let record_level = shared_local_ident(RECORD_LEVEL);
+        // With `registry` the override of the function was already checked instead of `log::max_level()`.
+        let max_level = match self.registry {
+            Some(_) => quote!(true),
+            None => quote!(#record_level <= log::max_level()),
+        };
+        quote! {{
+            let #record_level: log::Level = #level;
+            if #record_level <= log::STATIC_MAX_LEVEL && #max_level {
+                log::logger().log(
+                    &log::Record::builder()
+                        .args(format_args!(#fmt, #(#args),*))
+                        .level(#record_level)
+                        .target(module_path!())
+                        .module_path_static(Some(module_path!()))
+                        .file_static(Some(file!()))
+                        .line(Some(#line))
+                        .build(),
+                );
+            }
+        }}
+    }
+
+    /// The static holding the `dedupe` state, it must be in scope of every message.
+    fn dedupe_site(&self) -> TokenStream {
+        match self.dedupe {
+            Some(DedupeWindow(millis)) => {
+                let site = Ident::new(DEDUPE_SITE, proc_macro2::Span::call_site());
+                quote! {
+                    static #site: log_derive_runtime::dedupe::Dedupe =
+                        log_derive_runtime::dedupe::Dedupe::new(std::time::Duration::from_millis(#millis));
+                }
+            }
+            None => TokenStream::new(),
+        }
+    }
+
+    /// Whether any of the `levels` is enabled, used to skip the work only the messages need.
+    /// With `registry` it's checked by a site of each level, like the messages do.
+    fn enabled<'a>(&self, levels: impl IntoIterator<Item = &'a Ident>) -> TokenStream {
+        let levels: Vec<_> = levels.into_iter().map(get_logger_token).collect();
+        if levels.is_empty() {
+            return quote!(false);
+        }
+        match &self.registry {
+            Some(name) => {
+                let sites = levels.iter().map(|level| self.site_enabled(name, level));
+                quote!(#(#sites)||*)
+            }
+            None => quote!(#(log::log_enabled!(#levels))||*),
+        }
+    }
+
+    /// Whether the registry site of the function's messages at `level` is enabled.
+    fn site_enabled(&self, name: &str, level: &TokenStream) -> TokenStream {
+        quote! {{
+            static SITE: log_derive_runtime::registry::Site =
+                log_derive_runtime::registry::Site::new(concat!(module_path!(), "::", #name), #level);
+            SITE.enabled()
+        }}
+    }
+}
+
+/// Locals shared by the entry and exit logs are always the first statements of the body and start with this prefix,
+/// so when both macros are used on the same function whichever expands second can find (and reuse) them.
+const SHARED_LOCAL_PREFIX: &str = "__log_derive_";
+const CALL_ID: &str = "__log_derive_call_id";
+const DEPTH: &str = "__log_derive_depth";
+const DEPTH_GUARD: &str = "__log_derive_depth_guard";
+const CALLER: &str = "__log_derive_caller";
+/// The level chosen at runtime by `level_fn`. Not a shared local, but prefixed the same so it can't shadow
+/// an input that's being logged.
+const RUNTIME_LEVEL: &str = "__log_derive_level";
+/// Whether the `when` condition of the output holds, evaluated once the body returns.
+const WHEN: &str = "__log_derive_when";
+/// The level of a record, prefixed so it can't shadow an input that's being logged.
+const RECORD_LEVEL: &str = "__log_derive_record_level";
+/// The static declared by [`Decorations::dedupe_site`].
+const DEDUPE_SITE: &str = "__LOG_DERIVE_DEDUPE";
+
+fn shared_local_ident(name: &str) -> Ident {
+    Ident::new(name, proc_macro2::Span::call_site())
+}
+
+fn shared_local_name(stmt: &Stmt) -> Option<String> {
+    match stmt {
+        Stmt::Local(Local { pat: Pat::Ident(pat), .. }) => {
+            Some(pat.ident.to_string()).filter(|name| name.starts_with(SHARED_LOCAL_PREFIX))
+        }
+        _ => None,
+    }
+}
+
+/// Takes the shared locals the other macro already generated out of the block.
+fn take_shared_locals(block: &mut Block) -> Vec<Stmt> {
+    let count = block.stmts.iter().take_while(|stmt| shared_local_name(stmt).is_some()).count();
+    block.stmts.drain(..count).collect()
+}
+
+/// Adds the shared locals required by `call_id` and `depth` which weren't generated yet.
+fn add_shared_locals(shared: &mut Vec<Stmt>, call_id: bool, depth: bool, caller: bool, is_async: bool) {
+    let contains = |shared: &[Stmt], name| shared.iter().any(|stmt| shared_local_name(stmt).as_deref() == Some(name));
+    if call_id && !contains(shared, CALL_ID) {
+        // The counter is per function, so the id is unique together with the function name.
+        let call_id = shared_local_ident(CALL_ID);
+        shared.push(syn::parse_quote! {
+            let #call_id = {
+                static CALL_ID: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+                CALL_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+            };
+        });
+    }
+    if depth && !contains(shared, DEPTH) {
+        let depth = shared_local_ident(DEPTH);
+        if is_async {
+            // The body is polled in a `depth::scope`, because a guard held across an await would leak into other tasks.
+            shared.push(syn::parse_quote!(let #depth = log_derive_runtime::depth::current();));
+        } else {
+            let guard = shared_local_ident(DEPTH_GUARD);
+            shared.push(syn::parse_quote!(let #guard = log_derive_runtime::depth::enter();));
+            shared.push(syn::parse_quote!(let #depth = #guard.depth();));
+        }
+    }
+    if caller && !contains(shared, CALLER) {
+        // Captured before the body, which runs in a closure that doesn't track the caller.
+        let caller = shared_local_ident(CALLER);
+        shared.push(syn::parse_quote!(let #caller = std::panic::Location::caller();));
+    }
+}
+
+/// Adds `#[track_caller]` for the `caller` option, unless the other macro already did.
+fn track_caller(func: &mut ItemFn) -> std::result::Result<(), TokenStream> {
+    if let Some(asyncness) = &func.sig.asyncness {
+        let msg = "`caller` requires `#[track_caller]`, which isn't supported on async functions";
+        return Err(syn::Error::new_spanned(asyncness, msg).to_compile_error());
+    }
+    if !func.attrs.iter().any(|attr| attr.path.is_ident("track_caller")) {
+        func.attrs.push(syn::parse_quote!(#[track_caller]));
+    }
+    Ok(())
+}
+
+/// Wraps an async body so it's polled with the depth of its nested calls.
+fn scope_depth(body: Expr) -> Expr {
+    let depth = shared_local_ident(DEPTH);
+    syn::parse_quote!(log_derive_runtime::depth::scope(#depth + 1, #body))
+}
+
 fn get_logger_token(att: &Ident) -> TokenStream {
     // Capitalize the first letter.
     let attr_str = att.to_string().to_lowercase();
@@ -277,7 +1262,9 @@ fn get_logger_token(att: &Ident) -> TokenStream {
     quote!(log::Level::#att_str)
 }
 
-fn make_closure(original: &ItemFn) -> Expr {
+/// The body as an expression, it moves the inputs unless `borrow_inputs` is set as they're used after it.
+fn make_closure(original: &ItemFn, borrow_inputs: bool) -> Expr {
+    let capture = Some(token::Move { span: original.span() }).filter(|_| !borrow_inputs);
     match original.sig.asyncness {
         Some(asyncness) => Expr::Await(ExprAwait {
             attrs: Default::default(),
@@ -285,7 +1272,7 @@ fn make_closure(original: &ItemFn) -> Expr {
             dot_token: Default::default(),
             base: Box::new(syn::Expr::Async(ExprAsync {
                 attrs: Default::default(),
-                capture: Some(token::Move { span: original.span() }),
+                capture,
                 block: *original.block.clone(),
                 async_token: asyncness,
             })),
@@ -301,7 +1288,7 @@ fn make_closure(original: &ItemFn) -> Expr {
                     attrs: Default::default(),
                     asyncness: Default::default(),
                     movability: Default::default(),
-                    capture: Some(token::Move { span: original.span() }),
+                    capture,
                     or1_token: Default::default(),
                     inputs: Default::default(),
                     or2_token: Default::default(),
@@ -323,16 +1310,66 @@ fn replace_function_headers(original: ItemFn, new: &mut ItemFn) {
     new.block = block;
 }
 
+/// Wraps the returned iterator/stream so every yielded item is logged, and a summary is logged once it's exhausted.
+fn generate_sequence(closure: &Expr, expressions: FormattedAttributes, kind: SequenceKind) -> Result<ItemFn> {
+    let FormattedAttributes { each_expr, summary_expr, summary_enabled, dedupe_site, .. } = expressions;
+    let code = match kind {
+        SequenceKind::Iterator => quote! {
+            fn temp() {
+                #dedupe_site
+                let instant = if #summary_enabled { Some(std::time::Instant::now()) } else { None };
+                let mut iter = #closure;
+                let mut count: usize = 0;
+                let mut done = false;
+                std::iter::from_fn(move || {
+                    if done {
+                        return None;
+                    }
+                    match std::iter::Iterator::next(&mut iter) {
+                        Some(item) => { count += 1; #each_expr; Some(item) }
+                        None => { done = true; #summary_expr; None }
+                    }
+                })
+            }
+        },
+        SequenceKind::Stream => quote! {
+            fn temp() {
+                #dedupe_site
+                let instant = if #summary_enabled { Some(std::time::Instant::now()) } else { None };
+                let mut stream = std::boxed::Box::pin(#closure);
+                let mut count: usize = 0;
+                let mut done = false;
+                futures::stream::poll_fn(move |cx| {
+                    if done {
+                        return std::task::Poll::Ready(None);
+                    }
+                    match futures::stream::Stream::poll_next(stream.as_mut(), cx) {
+                        std::task::Poll::Ready(Some(item)) => { count += 1; #each_expr; std::task::Poll::Ready(Some(item)) }
+                        std::task::Poll::Ready(None) => { done = true; #summary_expr; std::task::Poll::Ready(None) }
+                        std::task::Poll::Pending => std::task::Poll::Pending,
+                    }
+                })
+            }
+        },
+    };
+
+    syn::parse2(code)
+}
+
 fn generate_function(closure: &Expr, expressions: FormattedAttributes, result: bool) -> Result<ItemFn> {
-    let FormattedAttributes { ok_expr, err_expr, log_ts, contained_ok_or_err } = expressions;
+    let FormattedAttributes { ok_expr, err_expr, log_ts, snapshots, output_enabled, when, dedupe_site, contained_ok_or_err, .. } =
+        expressions;
     let result = result || contained_ok_or_err;
     let code = if log_ts {
         if result {
             quote! {
                 fn temp() {
-                    let instant = std::time::Instant::now();
+                    #dedupe_site
+                    #snapshots
+                    let instant = if #output_enabled { Some(std::time::Instant::now()) } else { None };
                     let result = #closure;
-                    let ts = instant.elapsed();
+                    let ts = instant.map_or_else(std::time::Duration::default, |instant| instant.elapsed());
+                    #when
                     result.map(|result| { #ok_expr; result })
                         .map_err(|err| { #err_expr; err })
                 }
@@ -340,9 +1377,12 @@ fn generate_function(closure: &Expr, expressions: FormattedAttributes, result: b
         } else {
             quote! {
                 fn temp() {
-                    let instant = std::time::Instant::now();
+                    #dedupe_site
+                    #snapshots
+                    let instant = if #output_enabled { Some(std::time::Instant::now()) } else { None };
                     let result = #closure;
-                    let ts = instant.elapsed();
+                    let ts = instant.map_or_else(std::time::Duration::default, |instant| instant.elapsed());
+                    #when
                     #ok_expr;
                     result
                 }
@@ -351,7 +1391,10 @@ fn generate_function(closure: &Expr, expressions: FormattedAttributes, result: b
     } else if result {
         quote! {
             fn temp() {
+                #dedupe_site
+                #snapshots
                 let result = #closure;
+                #when
                 result.map(|result| { #ok_expr; result })
                     .map_err(|err| { #err_expr; err })
             }
@@ -359,7 +1402,10 @@ fn generate_function(closure: &Expr, expressions: FormattedAttributes, result: b
     } else {
         quote! {
             fn temp() {
+                #dedupe_site
+                #snapshots
                 let result = #closure;
+                #when
                 #ok_expr;
                 result
             }
@@ -385,20 +1431,58 @@ fn generate_function(closure: &Expr, expressions: FormattedAttributes, result: b
 /// ```
 #[proc_macro_attribute]
 pub fn logfn(attr: proc_macro::TokenStream, item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let (attr, err_levels) = match take_err_levels(attr.into()) {
+        Ok((attr, err_levels)) => (proc_macro::TokenStream::from(attr), err_levels),
+        Err(err) => return err.to_compile_error().into(),
+    };
     let attr = parse_macro_input!(attr as AttributeArgs);
+    let expand = |attr: &[NestedMeta], original_fn| expand_logfn(attr, &err_levels, original_fn);
+    if let Some(method) = parse_required_method(&item) {
+        return expand_required_method(method, |wrapper| expand_with_cfg(&attr, wrapper, expand)).into();
+    }
     let original_fn: ItemFn = parse_macro_input!(item as ItemFn);
-    let fmt_default = original_fn.sig.ident.to_string() + "() => {:?}";
-    let parsed_attributes: FormattedAttributes = match FormattedAttributes::parse_attributes(&attr, fmt_default) {
+    expand_with_cfg(&attr, original_fn, expand).into()
+}
+
+/// Closures and blocks can't be `#[track_caller]`, so they can't log their caller.
+const CALLER_UNSUPPORTED: &str = "the caller is only tracked for functions";
+/// Closures and blocks are wrapped without looking at their value's type, so it's never treated as a sequence.
+const SEQUENCE_UNSUPPORTED: &str = "the items of a sequence are only logged for functions";
+
+/// The `ItemFn` transformation of [`logfn`], on failure returns the compile errors.
+fn expand_logfn(attr: &[NestedMeta], err_levels: &[ErrLevel], mut original_fn: ItemFn) -> std::result::Result<ItemFn, TokenStream> {
+    let parsed_attributes: FormattedAttributes = match FormattedAttributes::parse_attributes(attr, err_levels, &original_fn.sig) {
         Ok(val) => val,
         Err(err) => {
-            return err.write_errors().into();
+            return Err(err.write_errors());
+        }
+    };
+    let mut shared_locals = take_shared_locals(&mut original_fn.block);
+    let is_async = original_fn.sig.asyncness.is_some();
+    let caller = parsed_attributes.caller;
+    add_shared_locals(&mut shared_locals, parsed_attributes.call_id, parsed_attributes.depth, caller, is_async);
+    let mut closure = make_closure(&original_fn, parsed_attributes.borrows_inputs);
+    if let (true, Expr::Await(closure)) = (parsed_attributes.depth, &mut closure) {
+        *closure.base = scope_depth(*closure.base.clone());
+    }
+    let mut new_fn = if parsed_attributes.contained_each_or_summary {
+        match check_if_return_sequence(&original_fn) {
+            Some(kind) => generate_sequence(&closure, parsed_attributes, kind).expect("Failed Generating Function"),
+            None => {
+                let msg = "`each` and `summary` require the function to return `impl Iterator` or `impl Stream`";
+                return Err(syn::Error::new_spanned(&original_fn.sig, msg).to_compile_error());
+            }
         }
+    } else {
+        let is_result = check_if_return_result(&original_fn);
+        generate_function(&closure, parsed_attributes, is_result).expect("Failed Generating Function")
     };
-    let closure = make_closure(&original_fn);
-    let is_result = check_if_return_result(&original_fn);
-    let mut new_fn = generate_function(&closure, parsed_attributes, is_result).expect("Failed Generating Function");
+    new_fn.block.stmts.splice(0..0, shared_locals);
     replace_function_headers(original_fn, &mut new_fn);
-    new_fn.into_token_stream().into()
+    if caller {
+        track_caller(&mut new_fn)?;
+    }
+    Ok(new_fn)
 }
 
 /// Logs the inputs of the function
@@ -417,24 +1501,178 @@ pub fn logfn(attr: proc_macro::TokenStream, item: proc_macro::TokenStream) -> pr
 /// ```
 #[proc_macro_attribute]
 pub fn logfn_inputs(attr: proc_macro::TokenStream, item: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let mut original_fn: ItemFn = parse_macro_input!(item as ItemFn);
-
     let attr = parse_macro_input!(attr as AttributeArgs);
-    let parsed_attributes = match InputOptions::from_list(&attr) {
+    if let Some(method) = parse_required_method(&item) {
+        return expand_required_method(method, |wrapper| expand_with_cfg(&attr, wrapper, expand_logfn_inputs)).into();
+    }
+    let original_fn: ItemFn = parse_macro_input!(item as ItemFn);
+    expand_with_cfg(&attr, original_fn, expand_logfn_inputs).into()
+}
+
+/// The `ItemFn` transformation of [`logfn_inputs`], on failure returns the compile errors.
+fn expand_logfn_inputs(attr: &[NestedMeta], mut original_fn: ItemFn) -> std::result::Result<ItemFn, TokenStream> {
+    let parsed_attributes = match InputOptions::from_list(attr) {
         Ok(val) => val,
         Err(err) => {
-            return err.write_errors().into();
+            return Err(err.write_errors());
         }
     };
 
-    let mut stmts = match log_fn_inputs(&original_fn, parsed_attributes) {
-        Ok(input_log) => vec![input_log],
-        Err(e) => return e.to_compile_error().into(),
+    let mut stmts = take_shared_locals(&mut original_fn.block);
+    let is_async = original_fn.sig.asyncness.is_some();
+    let depth = parsed_attributes.depth().is_some();
+    let caller = parsed_attributes.caller();
+    add_shared_locals(&mut stmts, parsed_attributes.call_id(), depth, caller, is_async);
+    match log_fn_inputs(&original_fn, parsed_attributes) {
+        Ok(input_log) => stmts.push(input_log),
+        Err(e) => return Err(e.to_compile_error()),
     };
 
-    stmts.extend(original_fn.block.stmts);
+    if depth && is_async {
+        let block = &original_fn.block;
+        let body = scope_depth(syn::parse_quote!(async move #block));
+        stmts.push(Stmt::Expr(syn::parse_quote!(#body.await)));
+    } else {
+        stmts.extend(original_fn.block.stmts);
+    }
     original_fn.block.stmts = stmts;
-    original_fn.into_token_stream().into()
+    if caller {
+        track_caller(&mut original_fn)?;
+    }
+    Ok(original_fn)
+}
+
+/// Trait methods with a default body are parsed as an `ItemFn`, so this only returns methods without one.
+fn parse_required_method(item: &proc_macro::TokenStream) -> Option<TraitItemMethod> {
+    syn::parse::<TraitItemMethod>(item.clone()).ok().filter(|method| method.default.is_none())
+}
+
+/// The required method is always split, so the methods the implementations provide don't depend on the `cfg` option.
+fn expand_required_method(method: TraitItemMethod, expand: impl FnOnce(ItemFn) -> TokenStream) -> TokenStream {
+    let (required, wrapper) = split_required_method(method);
+    let wrapper = expand(wrapper);
+    quote!(#required #wrapper)
+}
+
+/// Expands the function, and if the attribute has a `cfg = "PREDICATE"` option emits it only when the predicate holds
+/// and the original function otherwise, so the instrumentation can be compiled out entirely (e.g. in release builds).
+fn expand_with_cfg(
+    attr: &[NestedMeta],
+    original_fn: ItemFn,
+    expand: impl FnOnce(&[NestedMeta], ItemFn) -> std::result::Result<ItemFn, TokenStream>,
+) -> TokenStream {
+    let (cfg, attr): (Vec<_>, Vec<_>) = attr.iter().cloned().partition(|item| match item {
+        NestedMeta::Meta(Meta::NameValue(name_value)) => name_value.path.is_ident("cfg"),
+        _ => false,
+    });
+    let predicate = match cfg.first() {
+        Some(NestedMeta::Meta(Meta::NameValue(name_value))) => match &name_value.lit {
+            syn::Lit::Str(lit) => match lit.parse::<NestedMeta>() {
+                Ok(predicate) => Some(predicate),
+                Err(err) => return err.to_compile_error(),
+            },
+            lit => return Error::unexpected_lit_type(lit).at("cfg").write_errors(),
+        },
+        _ => None,
+    };
+    match (predicate, expand(&attr, original_fn.clone())) {
+        (_, Err(err)) => err,
+        (Some(predicate), Ok(new_fn)) => quote! {
+            #[cfg(#predicate)]
+            #new_fn
+            #[cfg(not(#predicate))]
+            #original_fn
+        },
+        (None, Ok(new_fn)) => new_fn.into_token_stream(),
+    }
+}
+
+/// A trait method without a body can't be instrumented, so it's split into a provided method with the same signature
+/// which can be instrumented, and a required `<name>_impl` method which it calls and the implementations provide instead.
+fn split_required_method(method: TraitItemMethod) -> (TraitItemMethod, ItemFn) {
+    let TraitItemMethod { attrs, mut sig, .. } = method;
+    // A method without a body can only have simple patterns, so there's nothing to bind.
+    let (args, _) = name_inputs(&mut sig);
+
+    let name = sig.ident.to_string();
+    let impl_ident = Ident::new(&format!("{}_impl", name), sig.ident.span());
+    let mut impl_sig = sig.clone();
+    impl_sig.ident = impl_ident.clone();
+    let doc = format!(" The implementation of [`{0}`](Self::{0}), which calls this method and logs it.", name);
+    let required = syn::parse_quote! {
+        #[doc = #doc]
+        #impl_sig;
+    };
+
+    // Lifetimes are inferred, but the types and consts might only be known from the wrapper's parameters.
+    let generics: Vec<_> = sig
+        .generics
+        .params
+        .iter()
+        .filter_map(|param| match param {
+            GenericParam::Type(param) => Some(&param.ident),
+            GenericParam::Const(param) => Some(&param.ident),
+            GenericParam::Lifetime(_) => None,
+        })
+        .collect();
+    let turbofish = if generics.is_empty() { TokenStream::new() } else { quote!(::<#(#generics),*>) };
+    let mut call = quote!(Self::#impl_ident #turbofish(#(#args),*));
+    if sig.asyncness.is_some() {
+        call = quote!(#call.await);
+    }
+    if sig.unsafety.is_some() {
+        call = quote!(unsafe { #call });
+    }
+    let wrapper = ItemFn { attrs, vis: Visibility::Inherited, sig, block: syn::parse_quote!({ #call }) };
+    (required, wrapper)
+}
+
+/// Gives a name to the inputs bound to patterns like `_` or `(a, b)`, so they can be logged and forwarded.
+/// Returns the names of all the inputs, and the statements binding the original patterns for the start of the body.
+fn name_inputs(sig: &mut Signature) -> (Vec<Ident>, Vec<Stmt>) {
+    let mut args = Vec::with_capacity(sig.inputs.len());
+    let mut bindings = Vec::new();
+    // Every identifier in the patterns, so a generated name can't clash with one of the inputs.
+    let mut used = Vec::new();
+    for input in &sig.inputs {
+        if let FnArg::Typed(pat_type) = input {
+            pattern_idents(pat_type.pat.to_token_stream(), &mut used);
+        }
+    }
+    for (i, input) in sig.inputs.iter_mut().enumerate() {
+        match input {
+            FnArg::Receiver(receiver) => args.push(Ident::from(receiver.self_token)),
+            FnArg::Typed(pat_type) => match pat_type.pat.as_ref() {
+                Pat::Ident(pat) => args.push(pat.ident.clone()),
+                pat => {
+                    let mut name = format!("arg{}", i);
+                    while used.contains(&name) {
+                        name.push('_');
+                    }
+                    let ident = Ident::new(&name, pat.span());
+                    used.push(name);
+                    match pat {
+                        Pat::Wild(_) => {}
+                        pat => bindings.push(syn::parse_quote!(let #pat = #ident;)),
+                    }
+                    *pat_type.pat = syn::parse_quote!(#ident);
+                    args.push(ident);
+                }
+            },
+        }
+    }
+    (args, bindings)
+}
+
+/// Collects every identifier of a pattern, including the paths of structs which only makes the check stricter.
+fn pattern_idents(tokens: TokenStream, idents: &mut Vec<String>) {
+    for token in tokens {
+        match token {
+            TokenTree::Ident(ident) => idents.push(ident.to_string()),
+            TokenTree::Group(group) => pattern_idents(group.stream(), idents),
+            _ => (),
+        }
+    }
 }
 
 fn log_fn_inputs(func: &ItemFn, attr: InputOptions) -> syn::Result<Stmt> {
@@ -445,23 +1683,40 @@ fn log_fn_inputs(func: &ItemFn, attr: InputOptions) -> syn::Result<Stmt> {
         .iter()
         .cloned()
         .map(|arg| match arg {
-            FnArg::Receiver(arg) => arg.self_token.into(),
-            FnArg::Typed(pat_type) => {
-                if let Pat::Ident(ident) = *pat_type.pat {
-                    ident.ident
-                } else {
-                    unimplemented!()
-                }
-            }
+            FnArg::Receiver(arg) => Ok(arg.self_token.into()),
+            FnArg::Typed(pat_type) => match *pat_type.pat {
+                Pat::Ident(ident) => Ok(ident.ident),
+                pat => Err(syn::Error::new_spanned(pat, "only inputs bound to an identifier can be logged")),
+            },
         })
-        .collect();
+        .collect::<Result<_>>()?;
+    let type_params: Vec<_> = func.sig.generics.type_params().map(|param| param.ident.clone()).collect();
+
+    log_inputs(&fn_name, &inputs, &type_params, func.sig.ident.span(), attr)
+}
 
-    let items: Punctuated<_, token::Comma> = inputs.iter().cloned().collect();
+/// The message suffix `, T={}, U={}` and its arguments, naming the types the generic function was instantiated with.
+fn type_names(type_params: &[Ident]) -> (String, Vec<TokenStream>) {
+    let fmt = type_params.iter().map(|param| format!(", {}={{}}", param)).collect();
+    let args = type_params.iter().map(|param| quote!(std::any::type_name::<#param>())).collect();
+    (fmt, args)
+}
 
+fn log_inputs(fn_name: &str, inputs: &[Ident], type_params: &[Ident], location: Span, attr: InputOptions) -> syn::Result<Stmt> {
     let level = get_logger_token(&attr.level);
-    let fmt = attr.fmt.unwrap_or_else(|| {
+    let decorations = Decorations::new(
+        attr.depth(),
+        attr.call_id(),
+        Some(fn_name).filter(|_| attr.registry()),
+        attr.sampling(),
+        attr.max_len(),
+        attr.named.dedupe,
+        location,
+    )
+    .with_caller(attr.caller());
+    let mut fmt = attr.fmt().unwrap_or_else(|| {
         let mut fmt = String::with_capacity(inputs.len() * 9);
-        fmt.push_str(&fn_name);
+        fmt.push_str(fn_name);
         fmt.push('(');
 
         for input in inputs {
@@ -473,12 +1728,403 @@ fn log_fn_inputs(func: &ItemFn, attr: InputOptions) -> syn::Result<Stmt> {
         fmt
     });
 
-    let res = quote! {
-        log::log!(#level, #fmt, #items);
+    let mut args = attr.input_args(inputs)?;
+    if attr.type_names() {
+        let (type_fmt, type_args) = type_names(type_params);
+        fmt += &type_fmt;
+        args.extend(type_args);
+    }
+
+    // The condition is part of the gate, so it's only evaluated if the level is enabled, before the sampling counters.
+    let gate = match (attr.when(), attr.sampling().gate()) {
+        (Some(condition), Some(gate)) => Some(quote!((#condition) && #gate)),
+        (Some(condition), None) => Some(quote!((#condition))),
+        (None, gate) => gate,
     };
+    let mut res = decorations.log_gated(&level, &fmt, args, gate);
+    if decorations.dedupe.is_some() {
+        let site = decorations.dedupe_site();
+        res = quote!({ #site #res });
+    }
     syn::parse2(res)
 }
 
+#[derive(Default, FromMeta)]
+#[darling(default)]
+struct AllNamedOptions {
+    output: Option<Ident>,
+    inputs: Option<Ident>,
+    registry: Option<bool>,
+}
+
+struct AllOptions {
+    /// The log level specified as the first word in the attribute.
+    leading_level: Option<Ident>,
+    named: AllNamedOptions,
+}
+
+impl AllOptions {
+    pub fn output_log(&self) -> Option<&Ident> {
+        self.named.output.as_ref().or(self.leading_level.as_ref())
+    }
+
+    pub fn inputs_log(&self) -> Option<&Ident> {
+        self.named.inputs.as_ref().or(self.leading_level.as_ref())
+    }
+
+    pub fn registry(&self) -> bool {
+        self.named.registry.unwrap_or(false)
+    }
+}
+
+impl FromMeta for AllOptions {
+    fn from_list(items: &[NestedMeta]) -> darling::Result<Self> {
+        let (leading_level, items) = split_leading_level(items)?;
+        let named = AllNamedOptions::from_list(items)?;
+
+        Ok(AllOptions { leading_level, named })
+    }
+}
+
+/// Logs the inputs and the result of every function in the `impl` block, `trait` or inline `mod` it's above.
+///
+/// The leading log level is used for both, `inputs = "LEVEL"` and `output = "LEVEL"` can set them separately. <br>
+/// `registry = true` is passed on to both, so their maximum levels can be changed at runtime. <br>
+/// A function can override them with its own [`logfn`] or [`logfn_inputs`] attribute, or opt-out with [`logfn_skip`]. <br>
+/// `const` functions are skipped as the logs can't be evaluated in a const context,
+/// and trait methods without a default body are instrumented like they are with [`logfn`]. <br>
+/// Traits inside a module are left alone, they need their own `logfn_all` (and impls providing the `<name>_impl` methods).
+/// # Examples
+/// ``` rust
+/// # use log_derive::{logfn, logfn_all, logfn_skip};
+/// #[derive(Debug)]
+/// struct Counter(u32);
+///
+/// #[logfn_all(Debug)]
+/// impl Counter {
+///     fn add(&mut self, n: u32) -> u32 {
+///         self.0 += n;
+///         self.0
+///     }
+///
+///     #[logfn(Trace, fmt = "get() => {}")]
+///     fn get(&self) -> u32 {
+///         self.0
+///     }
+///
+///     #[logfn_skip]
+///     fn new() -> Self {
+///         Counter(0)
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn logfn_all(attr: proc_macro::TokenStream, item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let attr = parse_macro_input!(attr as AttributeArgs);
+    let mut item: Item = parse_macro_input!(item as Item);
+    let options = match AllOptions::from_list(&attr) {
+        Ok(val) => val,
+        Err(err) => {
+            return err.write_errors().into();
+        }
+    };
+    match &item {
+        Item::Impl(_) | Item::Trait(_) | Item::Mod(ItemMod { content: Some(_), .. }) => (),
+        _ => {
+            let msg = "`logfn_all` can only be used on `impl` blocks, traits and inline modules";
+            return syn::Error::new_spanned(&item, msg).to_compile_error().into();
+        }
+    }
+    match instrument_item(&options, &mut item) {
+        Ok(()) => item.into_token_stream().into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// Excludes a function from [`logfn_all`], on its own it does nothing.
+#[proc_macro_attribute]
+pub fn logfn_skip(_attr: proc_macro::TokenStream, item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    item
+}
+
+fn instrument_item(options: &AllOptions, item: &mut Item) -> std::result::Result<(), TokenStream> {
+    match item {
+        Item::Fn(item_fn) => *item_fn = instrument_fn(options, item_fn.clone())?,
+        Item::Impl(item_impl) => {
+            for impl_item in &mut item_impl.items {
+                if let ImplItem::Method(method) = impl_item {
+                    let ImplItemMethod { attrs, vis, sig, block, .. } = method.clone();
+                    let new_fn = instrument_fn(options, ItemFn { attrs, vis, sig, block: Box::new(block) })?;
+                    method.attrs = new_fn.attrs;
+                    method.sig = new_fn.sig;
+                    method.block = *new_fn.block;
+                }
+            }
+        }
+        Item::Trait(item_trait) => {
+            let mut items = Vec::with_capacity(item_trait.items.len());
+            for trait_item in item_trait.items.drain(..) {
+                match trait_item {
+                    TraitItem::Method(method) if method.default.is_none() && !has_attr(&method.attrs, "logfn_skip") => {
+                        let (required, wrapper) = split_required_method(method);
+                        let wrapper = instrument_fn(options, wrapper)?;
+                        items.push(TraitItem::Method(required));
+                        items.push(syn::parse_quote!(#wrapper));
+                    }
+                    TraitItem::Method(mut method) => {
+                        if let Some(block) = method.default.take() {
+                            let item_fn =
+                                ItemFn { attrs: method.attrs, vis: Visibility::Inherited, sig: method.sig, block: Box::new(block) };
+                            let new_fn = instrument_fn(options, item_fn)?;
+                            method.attrs = new_fn.attrs;
+                            method.sig = new_fn.sig;
+                            method.default = Some(*new_fn.block);
+                        }
+                        items.push(TraitItem::Method(method));
+                    }
+                    trait_item => items.push(trait_item),
+                }
+            }
+            item_trait.items = items;
+        }
+        Item::Mod(ItemMod { content: Some((_, items)), .. }) => {
+            for item in items {
+                let attrs = match item {
+                    Item::Fn(item_fn) => &item_fn.attrs,
+                    Item::Impl(item_impl) => &item_impl.attrs,
+                    Item::Mod(item_mod) => &item_mod.attrs,
+                    // Splitting its required methods would break the impls next to it, which provide the original names.
+                    Item::Trait(_) => continue,
+                    _ => continue,
+                };
+                // Nested items with their own `logfn_all` are instrumented by it.
+                if !has_attr(attrs, "logfn_all") && !has_attr(attrs, "logfn_skip") {
+                    instrument_item(options, item)?;
+                }
+            }
+        }
+        _ => (),
+    }
+    Ok(())
+}
+
+fn instrument_fn(options: &AllOptions, mut item_fn: ItemFn) -> std::result::Result<ItemFn, TokenStream> {
+    if has_attr(&item_fn.attrs, "logfn_skip") || item_fn.sig.constness.is_some() {
+        return Ok(item_fn);
+    }
+    let registry = options.registry();
+    if let (Some(level), false) = (options.inputs_log(), has_attr(&item_fn.attrs, "logfn_inputs")) {
+        // Inputs like `_: u8` are common in trait impls, they're named so they can be logged.
+        let (_, bindings) = name_inputs(&mut item_fn.sig);
+        item_fn.block.stmts.splice(0..0, bindings);
+        item_fn = expand_logfn_inputs(&[syn::parse_quote!(#level), syn::parse_quote!(registry = #registry)], item_fn)?;
+    }
+    if let (Some(level), false) = (options.output_log(), has_attr(&item_fn.attrs, "logfn")) {
+        item_fn = expand_logfn(&[syn::parse_quote!(#level), syn::parse_quote!(registry = #registry)], &[], item_fn)?;
+    }
+    Ok(item_fn)
+}
+
+/// Check for an attribute by its last segment, so both `#[logfn]` and `#[log_derive::logfn]` are found.
+fn has_attr(attrs: &[Attribute], name: &str) -> bool {
+    attrs.iter().filter_map(|attr| attr.path.segments.last()).any(|segment| segment.ident == name)
+}
+
+#[derive(Default, FromMeta)]
+#[darling(default)]
+struct ClosureOptions {
+    name: Option<String>,
+    inputs: Option<Ident>,
+}
+
+/// The arguments of [`logclosure!`], the attribute options of [`logfn`] followed by the closure.
+struct ClosureArgs {
+    attr: Vec<NestedMeta>,
+    err_levels: Vec<ErrLevel>,
+    closure: ExprClosure,
+}
+
+impl Parse for ClosureArgs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let (mut attr, mut err_levels) = (Vec::new(), Vec::new());
+        while !(input.peek(Token![|]) || input.peek(Token![||]) || input.peek(Token![move])) {
+            parse_option(input, &mut attr, &mut err_levels)?;
+        }
+        let closure = input.parse()?;
+        Ok(ClosureArgs { attr, err_levels, closure })
+    }
+}
+
+/// Logs the result of a closure, and optionally its inputs.
+///
+/// It accepts the same options as [`logfn`] except `cfg`, `caller`, `each` and `summary`, followed by the closure, and in addition <br>
+/// `name = "NAME"` which is used in the messages instead of `closure`, and `inputs = "LEVEL"` which logs the inputs like [`logfn_inputs`]. <br>
+/// The result is checked for being a `Result` by the closure's return type, if it's annotated.
+/// # Examples
+/// ``` rust
+/// use log_derive::logclosure;
+///
+/// let total: u32 = vec![1, 2, 3]
+///     .into_iter()
+///     .map(logclosure!(Trace, name = "square", inputs = "Trace", |n: u32| n * n))
+///     .sum();
+///
+/// let parse = logclosure!(ok = "Debug", err = "Warn", |s: &str| -> Result<u32, std::num::ParseIntError> { s.parse() });
+/// assert!(parse("a").is_err());
+/// ```
+#[proc_macro]
+pub fn logclosure(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let ClosureArgs { attr, err_levels, closure } = parse_macro_input!(input as ClosureArgs);
+    match expand_logclosure(attr, &err_levels, closure) {
+        Ok(closure) => closure.into_token_stream().into(),
+        Err(err) => err.into(),
+    }
+}
+
+fn expand_logclosure(
+    attr: Vec<NestedMeta>,
+    err_levels: &[ErrLevel],
+    closure: ExprClosure,
+) -> std::result::Result<ExprClosure, TokenStream> {
+    // The closure specific options are split from the ones shared with `logfn`.
+    let (closure_attr, attr): (Vec<_>, Vec<_>) = attr.into_iter().partition(|item| match item {
+        NestedMeta::Meta(Meta::NameValue(name_value)) => name_value.path.is_ident("name") || name_value.path.is_ident("inputs"),
+        _ => false,
+    });
+    let closure_options = ClosureOptions::from_list(&closure_attr).map_err(|err| err.write_errors())?;
+    let output_options = OutputOptions::with_err_levels(&attr, err_levels).map_err(|err| err.write_errors())?;
+    let inputs: Vec<_> = closure.inputs.iter().filter_map(|input| closure_input_ident(input).ok()).collect();
+    output_options.check_inputs(Some(&inputs)).map_err(|err| err.write_errors())?;
+    if output_options.caller() {
+        return Err(Error::custom(CALLER_UNSUPPORTED).at("caller").write_errors());
+    }
+    if output_options.contains_each_or_summary() {
+        let option = if output_options.named.each.is_some() { "each" } else { "summary" };
+        return Err(Error::custom(SEQUENCE_UNSUPPORTED).at(option).write_errors());
+    }
+    let name = closure_options.name.unwrap_or_else(|| String::from("closure"));
+    let (call_id, depth, registry) = (output_options.call_id(), output_options.depth(), output_options.registry());
+
+    let mut stmts = Vec::new();
+    add_shared_locals(&mut stmts, call_id, depth.is_some(), false, false);
+    if let Some(level) = closure_options.inputs {
+        let inputs =
+            closure.inputs.iter().map(closure_input_ident).collect::<Result<Vec<_>>>().map_err(|err| err.to_compile_error())?;
+        let input_options = InputOptions {
+            level,
+            named: InputNamedOptions {
+                fmt: None,
+                call_id: Some(call_id),
+                depth,
+                registry: Some(registry),
+                sample: output_options.named.sample,
+                every: output_options.named.every,
+                max_per_sec: output_options.named.max_per_sec,
+                max_len: output_options.named.max_len,
+                dedupe: output_options.named.dedupe,
+                ..Default::default()
+            },
+        };
+        stmts.push(log_inputs(&name, &inputs, &[], Span::call_site(), input_options).map_err(|err| err.to_compile_error())?);
+    }
+
+    let is_result = match &closure.output {
+        ReturnType::Type(_, ty) => matches!(ty.as_ref(), Type::Path(path) if is_result_type(path)),
+        ReturnType::Default => false,
+    };
+    let (body, output) = (&closure.body, &closure.output);
+    // Not a `move` closure, so the captures of the original closure aren't moved out of it.
+    let inner: Expr = syn::parse_quote!((|| #output #body)());
+    let default_fmt = format!("{}() => {{:?}}", name);
+    let parsed_attributes = FormattedAttributes::get_ok_err_streams(output_options, &name, default_fmt, &[], Span::call_site());
+    let new_fn = generate_function(&inner, parsed_attributes, is_result).expect("Failed Generating Function");
+    stmts.extend(new_fn.block.stmts);
+
+    let mut new_closure = closure.clone();
+    new_closure.body = Box::new(Expr::Block(ExprBlock {
+        attrs: Default::default(),
+        label: Default::default(),
+        block: Block { brace_token: Default::default(), stmts },
+    }));
+    Ok(new_closure)
+}
+
+/// The arguments of [`log_block!`], the options of [`logfn`], the name of the block and the block itself.
+struct BlockArgs {
+    attr: Vec<NestedMeta>,
+    err_levels: Vec<ErrLevel>,
+    name: LitStr,
+    block: Block,
+}
+
+impl Parse for BlockArgs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let (mut attr, mut err_levels) = (Vec::new(), Vec::new());
+        while !input.peek(LitStr) {
+            parse_option(input, &mut attr, &mut err_levels)?;
+        }
+        let name = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let block = input.parse()?;
+        Ok(BlockArgs { attr, err_levels, name, block })
+    }
+}
+
+/// Logs the value of a block inside a function, and returns it.
+///
+/// It accepts the same options as [`logfn`] except `cfg`, `caller`, `each` and `summary`, followed by the name of the block and the block. <br>
+/// The block is evaluated in place, so `?` and `return` inside it apply to the surrounding function. <br>
+/// Because the block has no return type, it's treated as a `Result` only if `ok` or `err` are specified.
+/// # Examples
+/// ``` rust
+/// use log_derive::log_block;
+///
+/// fn load() -> Result<u32, std::num::ParseIntError> {
+///     let config = log_block!(Info, log_ts = true, "load config", { "42".parse::<u32>()? });
+///     let checked = log_block!(ok = "Debug", err = "Error", "check config", {
+///         if config > 100 { Err(config) } else { Ok(config) }
+///     });
+///     Ok(checked.unwrap_or(100))
+/// }
+/// # assert_eq!(load(), Ok(42));
+/// ```
+#[proc_macro]
+pub fn log_block(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let BlockArgs { attr, err_levels, name, block } = parse_macro_input!(input as BlockArgs);
+    // The block has no inputs, any local can be logged.
+    let options = OutputOptions::with_err_levels(&attr, &err_levels).and_then(|options| options.check_inputs(None).map(|_| options));
+    let options = match options {
+        Ok(options) => options,
+        Err(err) => return err.write_errors().into(),
+    };
+    if options.caller() {
+        return Error::custom(CALLER_UNSUPPORTED).at("caller").write_errors().into();
+    }
+    if options.contains_each_or_summary() {
+        let option = if options.named.each.is_some() { "each" } else { "summary" };
+        return Error::custom(SEQUENCE_UNSUPPORTED).at(option).write_errors().into();
+    }
+    let name = name.value();
+    let (call_id, depth) = (options.call_id(), options.depth());
+
+    let mut stmts = Vec::new();
+    add_shared_locals(&mut stmts, call_id, depth.is_some(), false, false);
+    let default_fmt = format!("{} => {{:?}}", name);
+    let parsed_attributes = FormattedAttributes::get_ok_err_streams(options, &name, default_fmt, &[], Span::call_site());
+    let block = Expr::Block(ExprBlock { attrs: Default::default(), label: Default::default(), block });
+    let new_fn = generate_function(&block, parsed_attributes, false).expect("Failed Generating Function");
+    stmts.extend(new_fn.block.stmts);
+    quote!({ #(#stmts)* }).into()
+}
+
+fn closure_input_ident(input: &Pat) -> Result<Ident> {
+    match input {
+        Pat::Ident(pat) => Ok(pat.ident.clone()),
+        Pat::Type(pat_type) => closure_input_ident(&pat_type.pat),
+        pat => Err(syn::Error::new_spanned(pat, "only inputs bound to an identifier can be logged")),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use syn::parse_quote;
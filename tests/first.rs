@@ -1,5 +1,5 @@
+#![allow(clippy::needless_return, clippy::let_and_return, clippy::upper_case_acronyms, clippy::needless_borrow)]
 mod test_logger;
-
 use crate::test_logger::THREAD_LOGGER;
 use log::Level;
 use log_derive::{logfn, logfn_inputs};
@@ -91,16 +91,16 @@ impl Test for Me {
 fn works() {
     test_logger::init();
     wrapped_function(5, "cool!");
-    THREAD_LOGGER.assert_last_log("wrapper_function returned ()", Level::Info, 7);
+    THREAD_LOGGER.assert_last_log("wrapper_function returned ()", Level::Info, 8);
     let a = AAAAAA;
     let _ = a.yoyoy(String::from("fds"), 55, vec![1u8; 12]);
-    THREAD_LOGGER.assert_last_log("yoyoy() => [0, 0, 0, 0, 0, 0, 0, 0]", Level::Info, 19);
+    THREAD_LOGGER.assert_last_log("yoyoy() => [0, 0, 0, 0, 0, 0, 0, 0]", Level::Info, 20);
     let mut b = Me(None);
     let tes = Tes(false);
     b.abc(tes).unwrap();
-    THREAD_LOGGER.assert_last_log("DB: \"Hi!\"", Level::Debug, 41);
+    THREAD_LOGGER.assert_last_log("DB: \"Hi!\"", Level::Debug, 42);
     // `b.abc` calls `third()` so we need to assert that log too.
-    THREAD_LOGGER.assert_last_log("third() => \"Hi!\"", Level::Info, 56);
+    THREAD_LOGGER.assert_last_log("third() => \"Hi!\"", Level::Info, 57);
     assert!(THREAD_LOGGER.is_empty())
 }
 
@@ -110,19 +110,15 @@ fn test_inputs() {
     let b = Me(Some(5));
     let tes = Tes(false);
     b.just_inputs(&tes).unwrap();
-    THREAD_LOGGER.assert_last_log("just_inputs(self: Me(Some(5)),err: Tes(false))", Level::Debug, 65);
+    THREAD_LOGGER.assert_last_log("just_inputs(self: Me(Some(5)),err: Tes(false))", Level::Debug, 66);
     b.both(&tes).unwrap();
 
     // Assert `b.both` input log
-    THREAD_LOGGER.assert_last_log("both() => \"Hi!\"", Level::Info, 75);
+    THREAD_LOGGER.assert_last_log("both() => \"Hi!\"", Level::Info, 76);
     // `b.both` calls `third()` so wee need to assert that log too
-    THREAD_LOGGER.assert_last_log("third() => \"Hi!\"", Level::Info, 56);
+    THREAD_LOGGER.assert_last_log("third() => \"Hi!\"", Level::Info, 57);
     // Assert `b.both` output log
-    // Due to a bug in rust stable we can't test the line number here. (rust-lang/rust#74035)
-    // THREAD_LOGGER.assert_last_log("both(self: Me(Some(5)),err: Tes(false))", Level::Trace, 74);
-    let log = THREAD_LOGGER.pop_log();
-    assert_eq!(log.msg, "both(self: Me(Some(5)),err: Tes(false))");
-    assert_eq!(log.level, Level::Trace);
+    THREAD_LOGGER.assert_last_log("both(self: Me(Some(5)),err: Tes(false))", Level::Trace, 76);
     assert!(THREAD_LOGGER.is_empty())
 }
 
@@ -130,17 +126,17 @@ fn test_inputs() {
 fn fail() {
     test_logger::init();
     wrapped_function(5, "cool!");
-    THREAD_LOGGER.assert_last_log("wrapper_function returned ()", Level::Info, 7);
+    THREAD_LOGGER.assert_last_log("wrapper_function returned ()", Level::Info, 8);
     let a = AAAAAA;
     let _ = a.yoyoy(String::from("fds"), 55, vec![1u8; 12]);
-    THREAD_LOGGER.assert_last_log("yoyoy() => [0, 0, 0, 0, 0, 0, 0, 0]", Level::Info, 19);
+    THREAD_LOGGER.assert_last_log("yoyoy() => [0, 0, 0, 0, 0, 0, 0, 0]", Level::Info, 20);
 
     let mut b = Me(None);
     let tes = Tes(true);
     assert_eq!(b.abc(tes), Err(E));
-    THREAD_LOGGER.assert_last_log("DB: E", Level::Trace, 41);
+    THREAD_LOGGER.assert_last_log("DB: E", Level::Trace, 42);
     // `b.abc` calls `third()` so wee need to assert that log too
-    THREAD_LOGGER.assert_last_log("third() => E", Level::Info, 56);
+    THREAD_LOGGER.assert_last_log("third() => E", Level::Info, 57);
 
     assert!(THREAD_LOGGER.is_empty())
 }
@@ -0,0 +1,56 @@
+mod test_logger;
+
+use crate::test_logger::THREAD_LOGGER;
+use log::Level;
+use log_derive::logfn;
+
+#[derive(Debug)]
+struct Counter(u32);
+
+impl Counter {
+    #[logfn(Info, after(self, buf))]
+    fn drain_into(&mut self, buf: &mut Vec<u32>) -> usize {
+        buf.push(self.0);
+        self.0 = 0;
+        buf.len()
+    }
+
+    #[logfn(ok = "Info", err = "Warn", after(self))]
+    fn take(&mut self, n: u32) -> Result<u32, u32> {
+        if n > self.0 {
+            return Err(self.0);
+        }
+        self.0 -= n;
+        Ok(n)
+    }
+}
+
+#[logfn(Debug, after(items), log_ts = true)]
+fn sort(items: &mut [u8]) {
+    items.sort_unstable()
+}
+
+#[test]
+fn after_mut_self() {
+    test_logger::init();
+    let mut counter = Counter(3);
+    let mut buf = vec![1];
+    assert_eq!(counter.drain_into(&mut buf), 2);
+    THREAD_LOGGER.assert_last_log("drain_into() => 2, self=Counter(0), buf=[1, 3]", Level::Info, 12);
+    counter.0 = 5;
+    assert_eq!(counter.take(2), Ok(2));
+    THREAD_LOGGER.assert_last_log("take() => 2, self=Counter(3)", Level::Info, 19);
+    assert_eq!(counter.take(4), Err(3));
+    THREAD_LOGGER.assert_last_log("take() => 3, self=Counter(3)", Level::Warn, 19);
+    assert!(THREAD_LOGGER.is_empty())
+}
+
+#[test]
+fn after_with_ts() {
+    test_logger::init();
+    let mut items = [3, 1, 2];
+    sort(&mut items);
+    let log = THREAD_LOGGER.pop_log();
+    assert!(log.msg.starts_with("sort() => (), items=[1, 2, 3], ts="), "{}", log.msg);
+    assert!(THREAD_LOGGER.is_empty())
+}
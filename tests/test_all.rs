@@ -0,0 +1,166 @@
+mod test_logger;
+
+use crate::test_logger::THREAD_LOGGER;
+use log::Level;
+use log_derive::{logfn, logfn_all, logfn_inputs, logfn_skip};
+
+#[derive(Debug)]
+struct Counter(u32);
+
+#[logfn_all(Debug, inputs = "Trace")]
+impl Counter {
+    #[logfn_skip]
+    fn new() -> Self {
+        Counter(0)
+    }
+
+    fn add(&mut self, n: u32) -> u32 {
+        self.0 += n;
+        self.0
+    }
+
+    #[logfn(Info, fmt = "get() returned {}")]
+    fn get(&self) -> u32 {
+        self.0
+    }
+
+    #[logfn_inputs(Info, fmt = "checking {:?} against {}")]
+    fn check(&self, n: u32) -> Result<u32, u32> {
+        if self.0 == n {
+            Ok(n)
+        } else {
+            Err(self.0)
+        }
+    }
+
+    const fn zero() -> u32 {
+        0
+    }
+}
+
+trait Named {
+    fn name(&self) -> &'static str;
+}
+
+#[logfn_all(Info)]
+impl Named for Counter {
+    fn name(&self) -> &'static str {
+        "counter"
+    }
+}
+
+#[logfn_all(output = "Warn")]
+mod math {
+    pub fn double(n: u32) -> u32 {
+        n * 2
+    }
+
+    pub mod nested {
+        pub fn half(n: u32) -> u32 {
+            n / 2
+        }
+    }
+}
+
+#[test]
+fn impl_block() {
+    test_logger::init();
+    let mut counter = Counter::new();
+    assert!(THREAD_LOGGER.is_empty());
+    assert_eq!(counter.add(2), 2);
+    THREAD_LOGGER.assert_last_log("add() => 2", Level::Debug, 17);
+    THREAD_LOGGER.assert_last_log("add(self: Counter(0),n: 2)", Level::Trace, 17);
+    assert_eq!(counter.get(), 2);
+    THREAD_LOGGER.assert_last_log("get() returned 2", Level::Info, 23);
+    assert_eq!(THREAD_LOGGER.pop_log().msg, "get(self: Counter(2))");
+    assert_eq!(counter.check(3), Err(2));
+    THREAD_LOGGER.assert_last_log("check() => 2", Level::Debug, 28);
+    assert_eq!(THREAD_LOGGER.pop_log().msg, "checking Counter(2) against 3");
+    assert_eq!(Counter::zero(), 0);
+    assert!(THREAD_LOGGER.is_empty())
+}
+
+#[test]
+fn trait_impl() {
+    test_logger::init();
+    assert_eq!(Counter(1).name(), "counter");
+    THREAD_LOGGER.assert_last_log("name() => \"counter\"", Level::Info, 47);
+    assert_eq!(THREAD_LOGGER.pop_log().msg, "name(self: Counter(1))");
+    assert!(THREAD_LOGGER.is_empty())
+}
+
+#[test]
+fn module() {
+    test_logger::init();
+    assert_eq!(math::double(2), 4);
+    THREAD_LOGGER.assert_last_log("double() => 4", Level::Warn, 54);
+    assert_eq!(math::nested::half(4), 2);
+    THREAD_LOGGER.assert_last_log("half() => 2", Level::Warn, 59);
+    assert!(THREAD_LOGGER.is_empty())
+}
+
+trait Scaled {
+    fn scaled(&self, factor: u32, point: (u32, u32)) -> u32;
+}
+
+#[logfn_all(Debug)]
+impl Scaled for Counter {
+    fn scaled(&self, _: u32, (x, y): (u32, u32)) -> u32 {
+        self.0 * (x + y)
+    }
+}
+
+#[test]
+fn pattern_inputs() {
+    test_logger::init();
+    assert_eq!(Counter(2).scaled(7, (1, 2)), 6);
+    THREAD_LOGGER.assert_last_log("scaled() => 6", Level::Debug, 108);
+    THREAD_LOGGER.assert_last_log("scaled(self: Counter(2),arg1: 7,arg2: (1, 2))", Level::Debug, 108);
+    assert!(THREAD_LOGGER.is_empty())
+}
+
+#[logfn_all(Info)]
+mod greetings {
+    pub trait Greet {
+        fn greet(&self) -> u8;
+
+        fn greet_twice(&self) -> u8 {
+            self.greet() * 2
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct Hello;
+
+    impl Greet for Hello {
+        fn greet(&self) -> u8 {
+            1
+        }
+    }
+}
+
+#[test]
+fn module_trait() {
+    use greetings::Greet;
+    test_logger::init();
+    assert_eq!(greetings::Hello.greet_twice(), 2);
+    THREAD_LOGGER.assert_last_log("greet() => 1", Level::Info, 136);
+    THREAD_LOGGER.assert_last_log("greet(self: Hello)", Level::Info, 136);
+    assert!(THREAD_LOGGER.is_empty())
+}
+
+#[logfn_all(Debug)]
+impl Counter {
+    fn pair(&self, arg2: u32, (a, b): (u32, u32)) -> u32 {
+        self.0 + arg2 + a * b
+    }
+}
+
+#[test]
+fn pattern_input_names() {
+    test_logger::init();
+    assert_eq!(Counter(1).pair(2, (3, 4)), 15);
+    THREAD_LOGGER.assert_last_log("pair() => 15", Level::Debug, 154);
+    THREAD_LOGGER.assert_last_log("pair(self: Counter(1),arg2: 2,arg2_: (3, 4))", Level::Debug, 154);
+    assert!(THREAD_LOGGER.is_empty())
+}
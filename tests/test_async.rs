@@ -1,10 +1,11 @@
 #![cfg(feature = "async_test")]
-
+#![allow(clippy::needless_return)]
 mod test_logger;
 
 use crate::test_logger::THREAD_LOGGER;
+use futures::stream::{self, Stream, StreamExt};
 use log::Level;
-use log_derive::logfn;
+use log_derive::{logfn, logfn_inputs};
 
 #[logfn(INFO)]
 async fn async_function(ok: bool) -> Result<&'static str, &'static str> {
@@ -21,9 +22,9 @@ fn async_works() {
 
     futures_executor::block_on(async {
         assert_eq!(async_function(true).await, Ok("async Ok"));
-        THREAD_LOGGER.assert_last_log("async_function() => \"async Ok\"", Level::Info, 9);
+        THREAD_LOGGER.assert_last_log("async_function() => \"async Ok\"", Level::Info, 11);
         assert_eq!(async_function(false).await, Err("async Err"));
-        THREAD_LOGGER.assert_last_log("async_function() => \"async Err\"", Level::Info, 9);
+        THREAD_LOGGER.assert_last_log("async_function() => \"async Err\"", Level::Info, 11);
         assert!(THREAD_LOGGER.is_empty())
     })
 }
@@ -34,8 +35,70 @@ fn async_works2() {
     let block = futures_executor::block_on;
 
     assert_eq!(block(async_function(true)), Ok("async Ok"));
-    THREAD_LOGGER.assert_last_log("async_function() => \"async Ok\"", Level::Info, 9);
+    THREAD_LOGGER.assert_last_log("async_function() => \"async Ok\"", Level::Info, 11);
     assert_eq!(block(async_function(false)), Err("async Err"));
-    THREAD_LOGGER.assert_last_log("async_function() => \"async Err\"", Level::Info, 9);
+    THREAD_LOGGER.assert_last_log("async_function() => \"async Err\"", Level::Info, 11);
+    assert!(THREAD_LOGGER.is_empty())
+}
+
+#[logfn(each = "Debug", summary = "Info")]
+async fn async_stream(len: u8) -> impl Stream<Item = u8> {
+    stream::iter(0..len)
+}
+
+#[test]
+fn async_stream_items() {
+    test_logger::init();
+
+    futures_executor::block_on(async {
+        let items: Vec<u8> = async_stream(2).await.collect().await;
+        assert_eq!(items, vec![0, 1]);
+        let summary = THREAD_LOGGER.pop_log();
+        assert!(summary.msg.starts_with("async_stream() finished after 2 items, ts="));
+        assert_eq!(summary.level, Level::Info);
+        THREAD_LOGGER.assert_last_log("async_stream() yielded 1", Level::Debug, 45);
+        THREAD_LOGGER.assert_last_log("async_stream() yielded 0", Level::Debug, 45);
+        assert!(THREAD_LOGGER.is_empty())
+    })
+}
+
+#[logfn(Info, depth = "number")]
+async fn async_inner(n: u8) -> u8 {
+    futures::future::ready(n).await
+}
+
+#[logfn(Info, depth = "number")]
+#[logfn_inputs(Debug, depth = "number")]
+async fn async_outer(n: u8) -> u8 {
+    let (a, b) = futures::join!(async_inner(n), async_inner(n + 1));
+    a + b
+}
+
+#[test]
+fn async_depth() {
+    test_logger::init();
+
+    assert_eq!(futures_executor::block_on(async_outer(1)), 3);
+    THREAD_LOGGER.assert_last_log("depth=0 async_outer() => 3", Level::Info, 72);
+    THREAD_LOGGER.assert_last_log("depth=1 async_inner() => 2", Level::Info, 66);
+    THREAD_LOGGER.assert_last_log("depth=1 async_inner() => 1", Level::Info, 66);
+    assert_eq!(THREAD_LOGGER.pop_log().msg, "depth=0 async_outer(n: 1)");
+    assert_eq!(log_derive_runtime::depth::current(), 0);
+    assert!(THREAD_LOGGER.is_empty())
+}
+
+#[logfn(Info, after(buf))]
+async fn async_fill(buf: &mut Vec<u8>, n: u8) -> usize {
+    buf.push(futures::future::ready(n).await);
+    buf.len()
+}
+
+#[test]
+fn async_after() {
+    test_logger::init();
+
+    let mut buf = vec![1];
+    assert_eq!(futures_executor::block_on(async_fill(&mut buf, 2)), 2);
+    THREAD_LOGGER.assert_last_log("async_fill() => 2, buf=[1, 2]", Level::Info, 91);
     assert!(THREAD_LOGGER.is_empty())
 }
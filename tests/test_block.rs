@@ -0,0 +1,81 @@
+mod test_logger;
+
+use crate::test_logger::THREAD_LOGGER;
+use log::Level;
+use log_derive::log_block;
+
+fn load(config: &str) -> Result<u32, std::num::ParseIntError> {
+    let value = log_block!(Info, "load config", { config.parse::<u32>()? * 2 });
+    log_block!(ok = "Debug", err = "Error", "check config", {
+        if value > 10 {
+            Err(value)
+        } else {
+            Ok(value)
+        }
+    })
+    .or(Ok(10))
+}
+
+#[test]
+fn block_value() {
+    test_logger::init();
+    assert_eq!(load("2"), Ok(4));
+    THREAD_LOGGER.assert_last_log("check config => 4", Level::Debug, 9);
+    THREAD_LOGGER.assert_last_log("load config => 4", Level::Info, 8);
+    assert_eq!(load("6"), Ok(10));
+    THREAD_LOGGER.assert_last_log("check config => 12", Level::Error, 9);
+    THREAD_LOGGER.assert_last_log("load config => 12", Level::Info, 8);
+    assert!(THREAD_LOGGER.is_empty())
+}
+
+#[test]
+fn block_early_return() {
+    test_logger::init();
+    assert!(load("a").is_err());
+    assert!(THREAD_LOGGER.is_empty())
+}
+
+#[test]
+fn block_ts() {
+    test_logger::init();
+    let value = log_block!(Trace, log_ts = true, "sum", { (1..4).sum::<u32>() });
+    assert_eq!(value, 6);
+    let log = THREAD_LOGGER.pop_log();
+    assert!(log.msg.starts_with("sum => 6, ts="));
+    assert_eq!(log.level, Level::Trace);
+    assert!(THREAD_LOGGER.is_empty())
+}
+
+#[test]
+fn block_err_levels() {
+    test_logger::init();
+    for n in 0..2 {
+        let _: Result<(), u32> = log_block!(err_levels(0 = "Debug", _ = "Warn"), "validate", {
+            if n > 0 {
+                Err(n)
+            } else {
+                Err(0)
+            }
+        });
+    }
+    THREAD_LOGGER.assert_last_log("validate => 1", Level::Warn, 53);
+    THREAD_LOGGER.assert_last_log("validate => 0", Level::Debug, 53);
+    assert!(THREAD_LOGGER.is_empty())
+}
+
+struct NoDebug(u32);
+
+impl std::fmt::Display for NoDebug {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "NoDebug({})", self.0)
+    }
+}
+
+#[test]
+fn block_display() {
+    test_logger::init();
+    let value = log_block!(Info, display = true, "wrap", { NoDebug(1) });
+    assert_eq!(value.0, 1);
+    THREAD_LOGGER.assert_last_log("wrap => NoDebug(1)", Level::Info, 77);
+    assert!(THREAD_LOGGER.is_empty())
+}
@@ -0,0 +1,41 @@
+mod test_logger;
+
+use crate::test_logger::THREAD_LOGGER;
+use log::Level;
+use log_derive::{logfn, logfn_inputs};
+
+#[logfn(Info, hex_result)]
+#[logfn_inputs(Debug, hex(payload), base64(key))]
+fn sign(payload: &[u8], key: [u8; 4], rounds: u32) -> Vec<u8> {
+    payload.iter().zip(key.iter().cycle()).map(|(a, b)| a ^ b).take(rounds as usize).collect()
+}
+
+#[logfn(ok = "Info", err = "Error", base64_result = true, max_len = 8)]
+fn encode(data: &[u8]) -> Result<Vec<u8>, String> {
+    if data.is_empty() {
+        Err(String::from("empty"))
+    } else {
+        Ok(data.to_vec())
+    }
+}
+
+#[test]
+fn hex_and_base64_inputs() {
+    test_logger::init();
+    assert_eq!(sign(&[0xde, 0xad, 0xbe, 0xef], [0xff, 0, 0xff, 0], 3), [0x21, 0xad, 0x41]);
+    THREAD_LOGGER.assert_last_log("sign() => 21ad41", Level::Info, 9);
+    THREAD_LOGGER.assert_last_log("sign(payload: deadbeef,key: /wD/AA==,rounds: 3)", Level::Debug, 9);
+    assert!(THREAD_LOGGER.is_empty())
+}
+
+#[test]
+fn base64_result() {
+    test_logger::init();
+    assert!(encode(b"hi").is_ok());
+    THREAD_LOGGER.assert_last_log("encode() => aGk=", Level::Info, 14);
+    assert!(encode(b"hello world").is_ok());
+    THREAD_LOGGER.assert_last_log("encode() => aGVsbG8g… (16 bytes)", Level::Info, 14);
+    assert!(encode(b"").is_err());
+    THREAD_LOGGER.assert_last_log("encode() => \"empty\"", Level::Error, 14);
+    assert!(THREAD_LOGGER.is_empty())
+}
@@ -0,0 +1,66 @@
+mod test_logger;
+
+use crate::test_logger::THREAD_LOGGER;
+use log::Level;
+use log_derive::{logfn, logfn_inputs};
+
+#[logfn(Info, call_id = true)]
+#[logfn_inputs(Debug, call_id = true)]
+fn output_first(a: u8) -> u8 {
+    a * 2
+}
+
+#[logfn_inputs(Debug, call_id = true)]
+#[logfn(Info, call_id = true)]
+fn inputs_first(a: u8) -> u8 {
+    a * 2
+}
+
+#[logfn(Info, call_id = true)]
+fn failing(a: u8) -> Result<u8, u8> {
+    Err(a)
+}
+
+fn split_call_id(msg: &str) -> (&str, usize) {
+    let idx = msg.rfind(", call_id=").unwrap();
+    (&msg[..idx], msg[idx + ", call_id=".len()..].parse().unwrap())
+}
+
+fn assert_paired_call(input_msg: &str, output_msg: &str) -> usize {
+    let exit = THREAD_LOGGER.pop_log();
+    let entry = THREAD_LOGGER.pop_log();
+    assert_eq!(entry.level, Level::Debug);
+    assert_eq!(exit.level, Level::Info);
+    let (entry_msg, entry_id) = split_call_id(&entry.msg);
+    let (exit_msg, exit_id) = split_call_id(&exit.msg);
+    assert_eq!(entry_msg, input_msg);
+    assert_eq!(exit_msg, output_msg);
+    assert_eq!(entry_id, exit_id);
+    entry_id
+}
+
+#[test]
+fn pairs_entry_and_exit() {
+    test_logger::init();
+    output_first(1);
+    let first = assert_paired_call("output_first(a: 1)", "output_first() => 2");
+    output_first(2);
+    let second = assert_paired_call("output_first(a: 2)", "output_first() => 4");
+    assert_ne!(first, second);
+
+    inputs_first(3);
+    let first = assert_paired_call("inputs_first(a: 3)", "inputs_first() => 6");
+    inputs_first(4);
+    let second = assert_paired_call("inputs_first(a: 4)", "inputs_first() => 8");
+    assert_ne!(first, second);
+    assert!(THREAD_LOGGER.is_empty())
+}
+
+#[test]
+fn output_only() {
+    test_logger::init();
+    let _ = failing(7);
+    let log = THREAD_LOGGER.pop_log();
+    assert_eq!(split_call_id(&log.msg).0, "failing() => 7");
+    assert!(THREAD_LOGGER.is_empty())
+}
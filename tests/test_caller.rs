@@ -0,0 +1,38 @@
+mod test_logger;
+
+use crate::test_logger::THREAD_LOGGER;
+use log::Level;
+use log_derive::{logfn, logfn_inputs};
+
+#[logfn(Info, caller = true)]
+#[logfn_inputs(Debug, caller = true)]
+fn double(n: u32) -> u32 {
+    n * 2
+}
+
+#[logfn(ok = "Info", err = "Warn", caller = true)]
+fn parse(s: &str) -> Result<u32, std::num::ParseIntError> {
+    s.parse()
+}
+
+#[test]
+fn caller_location() {
+    test_logger::init();
+
+    let line = line!() + 1;
+    assert_eq!(double(2), 4);
+    THREAD_LOGGER.assert_last_log(&format!("double() => 4, caller={}:{}:16", file!(), line), Level::Info, 9);
+    THREAD_LOGGER.assert_last_log(&format!("double(n: 2), caller={}:{}:16", file!(), line), Level::Debug, 9);
+    assert!(THREAD_LOGGER.is_empty())
+}
+
+#[test]
+fn caller_location_result() {
+    test_logger::init();
+
+    let line = line!() + 1;
+    assert!(parse("x").is_err());
+    let msg = format!("parse() => ParseIntError {{ kind: InvalidDigit }}, caller={}:{}:13", file!(), line);
+    THREAD_LOGGER.assert_last_log(&msg, Level::Warn, 14);
+    assert!(THREAD_LOGGER.is_empty())
+}
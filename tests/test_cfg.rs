@@ -0,0 +1,49 @@
+mod test_logger;
+
+use crate::test_logger::THREAD_LOGGER;
+use log::Level;
+use log_derive::{logfn, logfn_inputs};
+
+#[logfn(Info, cfg = "all()")]
+#[logfn_inputs(Debug, cfg = "any()")]
+fn enabled_output(n: u32) -> u32 {
+    n + 1
+}
+
+#[logfn(Info, cfg = "any()")]
+#[logfn_inputs(Debug, cfg = "all()")]
+fn enabled_inputs(n: u32) -> u32 {
+    n + 1
+}
+
+trait Store: std::fmt::Debug {
+    #[logfn(Info, cfg = "any()")]
+    fn load(&self) -> u32;
+}
+
+#[derive(Debug)]
+struct Memory;
+
+impl Store for Memory {
+    fn load_impl(&self) -> u32 {
+        1
+    }
+}
+
+#[test]
+fn cfg_predicates() {
+    test_logger::init();
+    assert_eq!(enabled_output(1), 2);
+    THREAD_LOGGER.assert_last_log("enabled_output() => 2", Level::Info, 9);
+    assert!(THREAD_LOGGER.is_empty());
+    assert_eq!(enabled_inputs(1), 2);
+    THREAD_LOGGER.assert_last_log("enabled_inputs(n: 1)", Level::Debug, 15);
+    assert!(THREAD_LOGGER.is_empty());
+}
+
+#[test]
+fn cfg_required_method() {
+    test_logger::init();
+    assert_eq!(Memory.load(), 1);
+    assert!(THREAD_LOGGER.is_empty());
+}
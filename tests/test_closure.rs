@@ -0,0 +1,48 @@
+mod test_logger;
+
+use crate::test_logger::THREAD_LOGGER;
+use log::Level;
+use log_derive::logclosure;
+
+#[test]
+fn closure_inputs() {
+    test_logger::init();
+    let total: u32 = vec![1, 2].into_iter().map(logclosure!(Debug, name = "square", inputs = "Trace", |n: u32| n * n)).sum();
+    assert_eq!(total, 5);
+    THREAD_LOGGER.assert_last_log("square() => 4", Level::Debug, 10);
+    THREAD_LOGGER.assert_last_log("square(n: 2)", Level::Trace, 10);
+    THREAD_LOGGER.assert_last_log("square() => 1", Level::Debug, 10);
+    THREAD_LOGGER.assert_last_log("square(n: 1)", Level::Trace, 10);
+    assert!(THREAD_LOGGER.is_empty())
+}
+
+#[test]
+fn closure_result() {
+    test_logger::init();
+    let offset = 1;
+    let parse =
+        logclosure!(ok = "Info", err = "Error", |s: &str| -> Result<u32, std::num::ParseIntError> { Ok(s.parse::<u32>()? + offset) });
+    assert_eq!(parse("1"), Ok(2));
+    THREAD_LOGGER.assert_last_log("closure() => 2", Level::Info, 24);
+    assert!(parse("a").is_err());
+    THREAD_LOGGER.assert_last_log("closure() => ParseIntError { kind: InvalidDigit }", Level::Error, 24);
+    assert!(THREAD_LOGGER.is_empty())
+}
+
+#[test]
+fn closure_err_levels() {
+    test_logger::init();
+    let check = logclosure!(err_levels(0 = "Debug", _ = "Warn"), |n: u32| -> Result<u32, u32> {
+        if n > 1 {
+            Ok(n)
+        } else {
+            Err(n)
+        }
+    });
+    assert_eq!(check(0), Err(0));
+    THREAD_LOGGER.assert_last_log("closure() => 0", Level::Debug, 35);
+    assert_eq!(check(1), Err(1));
+    THREAD_LOGGER.assert_last_log("closure() => 1", Level::Warn, 35);
+    assert_eq!(check(2), Ok(2));
+    assert!(THREAD_LOGGER.is_empty())
+}
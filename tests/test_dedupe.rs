@@ -0,0 +1,69 @@
+mod test_logger;
+
+use crate::test_logger::THREAD_LOGGER;
+use log::Level;
+use log_derive::{logfn, logfn_inputs};
+use std::{thread, time::Duration};
+
+#[logfn(ok = "Info", err = "Warn", dedupe = "1h")]
+fn fetch(fail: bool) -> Result<u8, &'static str> {
+    if fail {
+        Err("Timeout")
+    } else {
+        Ok(1)
+    }
+}
+
+#[logfn(Info, dedupe = "1ms")]
+fn ping() -> &'static str {
+    "pong"
+}
+
+#[logfn_inputs(Debug, dedupe = "1h")]
+fn retry(attempt: u8) -> u8 {
+    attempt
+}
+
+#[test]
+fn dedupe_output() {
+    test_logger::init();
+
+    for _ in 0..3 {
+        assert_eq!(fetch(true), Err("Timeout"));
+    }
+    THREAD_LOGGER.assert_last_log("fetch() => \"Timeout\"", Level::Warn, 9);
+    assert!(THREAD_LOGGER.is_empty());
+
+    assert_eq!(fetch(false), Ok(1));
+    assert_eq!(fetch(false), Ok(1));
+    THREAD_LOGGER.assert_last_log("fetch() => 1", Level::Info, 9);
+    THREAD_LOGGER.assert_last_log("fetch() => \"Timeout\" (repeated 2 times)", Level::Warn, 9);
+    assert!(THREAD_LOGGER.is_empty())
+}
+
+#[test]
+fn dedupe_window() {
+    test_logger::init();
+
+    ping();
+    ping();
+    thread::sleep(Duration::from_millis(5));
+    ping();
+    THREAD_LOGGER.assert_last_log("ping() => \"pong\"", Level::Info, 18);
+    THREAD_LOGGER.assert_last_log("ping() => \"pong\" (repeated 1 times)", Level::Info, 18);
+    THREAD_LOGGER.assert_last_log("ping() => \"pong\"", Level::Info, 18);
+    assert!(THREAD_LOGGER.is_empty())
+}
+
+#[test]
+fn dedupe_inputs() {
+    test_logger::init();
+
+    retry(1);
+    retry(1);
+    retry(2);
+    THREAD_LOGGER.assert_last_log("retry(attempt: 2)", Level::Debug, 23);
+    THREAD_LOGGER.assert_last_log("retry(attempt: 1) (repeated 1 times)", Level::Debug, 23);
+    THREAD_LOGGER.assert_last_log("retry(attempt: 1)", Level::Debug, 23);
+    assert!(THREAD_LOGGER.is_empty())
+}
@@ -0,0 +1,56 @@
+mod test_logger;
+
+use crate::test_logger::THREAD_LOGGER;
+use log::Level;
+use log_derive::{logfn, logfn_inputs};
+
+#[logfn(Info, depth = "indent")]
+#[logfn_inputs(Debug, depth = "indent")]
+fn outer(n: u8) -> u8 {
+    inner(n) + middle(n)
+}
+
+#[logfn(Info, depth = "indent")]
+fn middle(n: u8) -> u8 {
+    inner(n)
+}
+
+#[logfn_inputs(Debug, depth = "indent")]
+#[logfn(Info, depth = "indent")]
+fn inner(n: u8) -> u8 {
+    n * 2
+}
+
+#[logfn(Info, depth = "number")]
+fn numbered(n: u8) -> Result<u8, u8> {
+    if n == 0 {
+        Err(n)
+    } else {
+        numbered(n - 1)
+    }
+}
+
+#[test]
+fn call_tree() {
+    test_logger::init();
+    assert_eq!(outer(1), 4);
+    THREAD_LOGGER.assert_last_log("outer() => 4", Level::Info, 9);
+    THREAD_LOGGER.assert_last_log("  middle() => 2", Level::Info, 14);
+    THREAD_LOGGER.assert_last_log("    inner() => 2", Level::Info, 20);
+    assert_eq!(THREAD_LOGGER.pop_log().msg, "    inner(n: 1)");
+    THREAD_LOGGER.assert_last_log("  inner() => 2", Level::Info, 20);
+    assert_eq!(THREAD_LOGGER.pop_log().msg, "  inner(n: 1)");
+    assert_eq!(THREAD_LOGGER.pop_log().msg, "outer(n: 1)");
+    assert_eq!(log_derive_runtime::depth::current(), 0);
+    assert!(THREAD_LOGGER.is_empty())
+}
+
+#[test]
+fn depth_number() {
+    test_logger::init();
+    assert_eq!(numbered(2), Err(0));
+    THREAD_LOGGER.assert_last_log("depth=0 numbered() => 0", Level::Info, 25);
+    THREAD_LOGGER.assert_last_log("depth=1 numbered() => 0", Level::Info, 25);
+    THREAD_LOGGER.assert_last_log("depth=2 numbered() => 0", Level::Info, 25);
+    assert!(THREAD_LOGGER.is_empty())
+}
@@ -0,0 +1,65 @@
+mod test_logger;
+
+use crate::test_logger::THREAD_LOGGER;
+use log::Level;
+use log_derive::{logfn, logfn_inputs};
+
+#[derive(Clone, Debug, PartialEq)]
+enum State {
+    Idle,
+    Running(u32),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Machine {
+    state: State,
+}
+
+impl Machine {
+    #[logfn(Info, diff(state))]
+    #[logfn_inputs(Debug)]
+    fn step(&mut self, state: &mut State, input: u32) -> bool {
+        if input > 0 {
+            *state = State::Running(input);
+        }
+        self.state = state.clone();
+        input > 0
+    }
+
+    #[logfn(ok = "Info", err = "Error", diff(self), log_ts = true)]
+    fn stop(&mut self) -> Result<(), ()> {
+        if self.state == State::Idle {
+            return Err(());
+        }
+        self.state = State::Idle;
+        Ok(())
+    }
+}
+
+#[test]
+fn diff_changes() {
+    test_logger::init();
+    let mut machine = Machine { state: State::Idle };
+    let mut state = State::Idle;
+    assert!(!machine.step(&mut state, 0));
+    THREAD_LOGGER.assert_last_log("step() => false, state: unchanged", Level::Info, 21);
+    assert_eq!(THREAD_LOGGER.pop_log().msg, "step(self: Machine { state: Idle },state: Idle,input: 0)");
+    assert!(machine.step(&mut state, 2));
+    THREAD_LOGGER.assert_last_log("step() => true, state: Idle -> Running(2)", Level::Info, 21);
+    assert_eq!(THREAD_LOGGER.pop_log().msg, "step(self: Machine { state: Idle },state: Idle,input: 2)");
+    assert!(THREAD_LOGGER.is_empty())
+}
+
+#[test]
+fn diff_self() {
+    test_logger::init();
+    let mut machine = Machine { state: State::Running(1) };
+    assert_eq!(machine.stop(), Ok(()));
+    let log = THREAD_LOGGER.pop_log();
+    assert!(log.msg.starts_with("stop() => (), self: Machine { state: Running(1) } -> Machine { state: Idle }, ts="), "{}", log.msg);
+    assert_eq!(machine.stop(), Err(()));
+    let log = THREAD_LOGGER.pop_log();
+    assert!(log.msg.starts_with("stop() => (), self: unchanged, ts="), "{}", log.msg);
+    assert_eq!(log.level, Level::Error);
+    assert!(THREAD_LOGGER.is_empty())
+}
@@ -0,0 +1,63 @@
+mod test_logger;
+
+use crate::test_logger::THREAD_LOGGER;
+use log::Level;
+use log_derive::logfn;
+use std::fmt;
+
+struct Celsius(f32);
+
+impl fmt::Display for Celsius {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}°C", self.0)
+    }
+}
+
+struct Opaque {
+    id: u32,
+}
+
+fn render(opaque: &Opaque) -> String {
+    format!("Opaque #{}", opaque.id)
+}
+
+#[logfn(Info, display = true)]
+fn temperature() -> Celsius {
+    Celsius(21.5)
+}
+
+#[logfn(Info, display = true)]
+fn read(fail: bool) -> Result<Celsius, &'static str> {
+    if fail {
+        Err("disconnected")
+    } else {
+        Ok(Celsius(-3.0))
+    }
+}
+
+#[logfn(Info, with = "render")]
+fn open(id: u32) -> Opaque {
+    Opaque { id }
+}
+
+#[test]
+fn display_output() {
+    test_logger::init();
+
+    assert_eq!(temperature().0, 21.5);
+    THREAD_LOGGER.assert_last_log("temperature() => 21.5°C", Level::Info, 25);
+    assert!(read(false).is_ok());
+    THREAD_LOGGER.assert_last_log("read() => -3°C", Level::Info, 30);
+    assert!(read(true).is_err());
+    THREAD_LOGGER.assert_last_log("read() => \"disconnected\"", Level::Info, 30);
+    assert!(THREAD_LOGGER.is_empty())
+}
+
+#[test]
+fn with_output() {
+    test_logger::init();
+
+    assert_eq!(open(7).id, 7);
+    THREAD_LOGGER.assert_last_log("open() => \"Opaque #7\"", Level::Info, 39);
+    assert!(THREAD_LOGGER.is_empty())
+}
@@ -0,0 +1,73 @@
+mod test_logger;
+
+use crate::test_logger::THREAD_LOGGER;
+use log::Level;
+use log_derive::logfn;
+
+// The fields are only read by `Debug`.
+#[allow(dead_code)]
+#[derive(Debug)]
+enum FetchError {
+    NotFound,
+    Timeout(u32),
+    Corrupted,
+}
+
+#[logfn(ok = "Info", err_levels(FetchError::NotFound = "Debug", FetchError::Timeout(_) = "Warn", _ = "Error"))]
+fn fetch(key: u32) -> Result<u32, FetchError> {
+    match key {
+        0 => Err(FetchError::NotFound),
+        1 => Err(FetchError::Timeout(30)),
+        2 => Err(FetchError::Corrupted),
+        key => Ok(key),
+    }
+}
+
+#[logfn(err_levels(FetchError::Timeout(_) = "Error"))]
+fn fetch_slow(key: u32) -> Result<u32, FetchError> {
+    fetch(key)
+}
+
+#[logfn(Trace, err_levels(FetchError::NotFound = "Debug"))]
+fn fetch_default(key: u32) -> Result<u32, FetchError> {
+    fetch(key)
+}
+
+#[test]
+fn variant_levels() {
+    test_logger::init();
+    assert!(fetch(3).is_ok());
+    THREAD_LOGGER.assert_last_log("fetch() => 3", Level::Info, 17);
+    assert!(fetch(0).is_err());
+    THREAD_LOGGER.assert_last_log("fetch() => NotFound", Level::Debug, 17);
+    assert!(fetch(1).is_err());
+    THREAD_LOGGER.assert_last_log("fetch() => Timeout(30)", Level::Warn, 17);
+    assert!(fetch(2).is_err());
+    THREAD_LOGGER.assert_last_log("fetch() => Corrupted", Level::Error, 17);
+    assert!(THREAD_LOGGER.is_empty())
+}
+
+#[test]
+fn only_err_levels() {
+    test_logger::init();
+    assert!(fetch_slow(1).is_err());
+    THREAD_LOGGER.assert_last_log("fetch_slow() => Timeout(30)", Level::Error, 27);
+    THREAD_LOGGER.assert_last_log("fetch() => Timeout(30)", Level::Warn, 17);
+    assert!(fetch_slow(0).is_err());
+    THREAD_LOGGER.assert_last_log("fetch() => NotFound", Level::Debug, 17);
+    assert!(fetch_slow(4).is_ok());
+    THREAD_LOGGER.assert_last_log("fetch() => 4", Level::Info, 17);
+    assert!(THREAD_LOGGER.is_empty())
+}
+
+#[test]
+fn fallback_level() {
+    test_logger::init();
+    assert!(fetch_default(0).is_err());
+    THREAD_LOGGER.assert_last_log("fetch_default() => NotFound", Level::Debug, 32);
+    THREAD_LOGGER.pop_log();
+    assert!(fetch_default(2).is_err());
+    THREAD_LOGGER.assert_last_log("fetch_default() => Corrupted", Level::Trace, 32);
+    THREAD_LOGGER.pop_log();
+    assert!(THREAD_LOGGER.is_empty())
+}
@@ -0,0 +1,55 @@
+mod test_logger;
+
+use crate::test_logger::THREAD_LOGGER;
+use log::Level;
+use log_derive::logfn;
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug)]
+struct ConfigError(std::num::ParseIntError);
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("invalid config")
+    }
+}
+
+impl Error for ConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+#[logfn(ok = "Info", err = "Error", err_chain = true)]
+fn load(port: &str) -> Result<u16, ConfigError> {
+    port.parse().map_err(ConfigError)
+}
+
+#[logfn(ok = "Info", err = "Error", err_chain = true, backtrace = true)]
+fn load_boxed(port: &str) -> Result<u16, Box<dyn Error>> {
+    load(port).map_err(|err| Box::new(err) as Box<dyn Error>)
+}
+
+#[test]
+fn error_chain() {
+    test_logger::init();
+    assert_eq!(load("80").unwrap(), 80);
+    THREAD_LOGGER.assert_last_log("load() => 80", Level::Info, 25);
+    assert!(load("port").is_err());
+    THREAD_LOGGER.assert_last_log("load() => invalid config, caused by: invalid digit found in string", Level::Error, 25);
+    assert!(THREAD_LOGGER.is_empty())
+}
+
+#[test]
+fn boxed_error_backtrace() {
+    test_logger::init();
+    assert!(load_boxed("port").is_err());
+    let log = THREAD_LOGGER.pop_log();
+    let expected = "load_boxed() => invalid config, caused by: invalid digit found in string, backtrace:\n";
+    assert!(log.msg.starts_with(expected), "{}", log.msg);
+    assert_eq!(log.level, Level::Error);
+    // The error logged by the inner `load()`.
+    THREAD_LOGGER.pop_log();
+    assert!(THREAD_LOGGER.is_empty())
+}
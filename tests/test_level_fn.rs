@@ -0,0 +1,61 @@
+mod test_logger;
+
+use crate::test_logger::THREAD_LOGGER;
+use log::Level;
+use log_derive::logfn;
+
+fn classify(status: &u16) -> Level {
+    match status {
+        500..=599 => Level::Error,
+        400..=499 => Level::Warn,
+        _ => Level::Info,
+    }
+}
+
+mod http {
+    pub fn classify<T>(response: &[T]) -> log::Level {
+        if response.is_empty() {
+            log::Level::Warn
+        } else {
+            log::Level::Debug
+        }
+    }
+}
+
+#[logfn(level_fn = "classify")]
+fn respond(status: u16) -> u16 {
+    status
+}
+
+#[logfn(err = "Error", level_fn = "http::classify", fmt = "got {:?}")]
+fn fetch(len: usize) -> Result<Vec<u8>, String> {
+    if len > 4 {
+        Err(String::from("too long"))
+    } else {
+        Ok(vec![0; len])
+    }
+}
+
+#[test]
+fn level_by_value() {
+    test_logger::init();
+    respond(200);
+    THREAD_LOGGER.assert_last_log("respond() => 200", Level::Info, 26);
+    respond(404);
+    THREAD_LOGGER.assert_last_log("respond() => 404", Level::Warn, 26);
+    respond(503);
+    THREAD_LOGGER.assert_last_log("respond() => 503", Level::Error, 26);
+    assert!(THREAD_LOGGER.is_empty())
+}
+
+#[test]
+fn level_by_ok_value() {
+    test_logger::init();
+    assert!(fetch(0).is_ok());
+    THREAD_LOGGER.assert_last_log("got []", Level::Warn, 31);
+    assert!(fetch(1).is_ok());
+    THREAD_LOGGER.assert_last_log("got [0]", Level::Debug, 31);
+    assert!(fetch(5).is_err());
+    THREAD_LOGGER.assert_last_log("got \"too long\"", Level::Error, 31);
+    assert!(THREAD_LOGGER.is_empty())
+}
@@ -1,3 +1,6 @@
+// Not every test file uses every helper.
+#![allow(dead_code)]
+
 use log::{Level, LevelFilter, Log, Metadata, Record};
 use std::cell::RefCell;
 
@@ -0,0 +1,41 @@
+mod test_logger;
+
+use crate::test_logger::THREAD_LOGGER;
+use log::Level;
+use log_derive::logfn_inputs;
+
+struct Secret(#[allow(dead_code)] u64);
+
+#[derive(Clone)]
+struct Item;
+
+#[logfn_inputs(Debug, maybe_debug = true)]
+fn store(key: &str, secret: Secret) -> u64 {
+    secret.0
+}
+
+#[logfn_inputs(Trace, maybe_debug = true)]
+fn process<T: Clone>(item: T, count: usize) -> Vec<T> {
+    vec![item; count]
+}
+
+#[test]
+fn maybe_debug_inputs() {
+    test_logger::init();
+
+    assert_eq!(store("a", Secret(1)), 1);
+    THREAD_LOGGER.assert_last_log("store(key: \"a\",secret: <test_maybe_debug::Secret>)", Level::Debug, 13);
+    assert!(THREAD_LOGGER.is_empty())
+}
+
+#[test]
+fn maybe_debug_generic() {
+    test_logger::init();
+
+    assert_eq!(process(Item, 2).len(), 2);
+    THREAD_LOGGER.assert_last_log("process(item: <test_maybe_debug::Item>,count: 2)", Level::Trace, 18);
+    // Even types implementing `Debug` are logged by their type name, as only the bounds are known.
+    assert_eq!(process(5u8, 1), vec![5]);
+    THREAD_LOGGER.assert_last_log("process(item: <u8>,count: 1)", Level::Trace, 18);
+    assert!(THREAD_LOGGER.is_empty())
+}
@@ -0,0 +1,72 @@
+mod test_logger;
+
+use crate::test_logger::THREAD_LOGGER;
+use log::{Level, LevelFilter};
+use log_derive::{logfn, logfn_inputs};
+use log_derive_runtime::registry::{self, Registered};
+
+#[logfn(Info, registry = true)]
+#[logfn_inputs(Debug, registry = true)]
+fn query(id: u32) -> u32 {
+    id * 2
+}
+
+mod db {
+    use log_derive::logfn;
+
+    #[logfn(ok = "Info", err = "Error", registry = true)]
+    pub fn connect(fail: bool) -> Result<(), ()> {
+        if fail {
+            Err(())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[test]
+fn runtime_levels() {
+    // The environment is only read on the first lookup, so everything is checked in a single test.
+    std::env::set_var(registry::ENV_VAR, "test_registry::query=trace");
+    test_logger::init();
+    // An override replaces `log::max_level()` for its functions, without changing the level of their messages.
+    log::set_max_level(LevelFilter::Info);
+
+    assert_eq!(query(1), 2);
+    THREAD_LOGGER.assert_last_log("query() => 2", Level::Info, 10);
+    THREAD_LOGGER.assert_last_log("query(id: 1)", Level::Debug, 10);
+
+    registry::set_level("test_registry::query", LevelFilter::Off);
+    assert_eq!(query(2), 4);
+    assert!(THREAD_LOGGER.is_empty());
+
+    registry::reset_level("test_registry::query");
+    assert_eq!(query(3), 6);
+    THREAD_LOGGER.assert_last_log("query() => 6", Level::Info, 10);
+    assert!(THREAD_LOGGER.is_empty());
+
+    // A module overrides every function in it.
+    registry::set_level("test_registry::db", LevelFilter::Warn);
+    assert_eq!(db::connect(false), Ok(()));
+    assert!(THREAD_LOGGER.is_empty());
+    assert_eq!(db::connect(true), Err(()));
+    THREAD_LOGGER.assert_last_log("connect() => ()", Level::Error, 18);
+    registry::set_level("test_registry::db::connect", LevelFilter::Debug);
+    assert_eq!(db::connect(false), Ok(()));
+    THREAD_LOGGER.assert_last_log("connect() => ()", Level::Info, 18);
+    assert_eq!(db::connect(true), Err(()));
+    THREAD_LOGGER.assert_last_log("connect() => ()", Level::Error, 18);
+    assert!(THREAD_LOGGER.is_empty());
+
+    let registered = registry::registered();
+    assert!(registered.contains(&Registered { name: "test_registry::query", level: Level::Debug }));
+    assert!(registered.contains(&Registered { name: "test_registry::query", level: Level::Info }));
+    assert!(registered.contains(&Registered { name: "test_registry::db::connect", level: Level::Info }));
+    assert!(registered.contains(&Registered { name: "test_registry::db::connect", level: Level::Error }));
+}
+
+#[test]
+fn parse_overrides() {
+    let parsed = registry::parse("a::b=trace, c = off,invalid,d=loud");
+    assert_eq!(parsed, vec![(String::from("a::b"), LevelFilter::Trace), (String::from("c"), LevelFilter::Off)]);
+}
@@ -0,0 +1,83 @@
+mod test_logger;
+
+use crate::test_logger::THREAD_LOGGER;
+use log::Level;
+use log_derive::{logfn, logfn_inputs};
+
+#[logfn(Info, every = 3)]
+fn every_third(n: u32) -> u32 {
+    n
+}
+
+#[logfn(Info, sample = 0.5)]
+#[logfn_inputs(Debug, sample = 0.5)]
+fn half(n: u32) -> u32 {
+    n
+}
+
+#[logfn(Info, max_per_sec = 2)]
+fn limited(n: u32) -> u32 {
+    n
+}
+
+#[logfn(ok = "Info", err = "Error", every = 1000)]
+fn parse(s: &str) -> Result<u32, std::num::ParseIntError> {
+    s.parse()
+}
+
+fn drain() -> Vec<test_logger::LogRecord> {
+    let mut logs = Vec::new();
+    while !THREAD_LOGGER.is_empty() {
+        logs.push(THREAD_LOGGER.pop_log());
+    }
+    logs
+}
+
+#[test]
+fn every() {
+    test_logger::init();
+    for n in 0..7 {
+        every_third(n);
+    }
+    THREAD_LOGGER.assert_last_log("every_third() => 6", Level::Info, 8);
+    THREAD_LOGGER.assert_last_log("every_third() => 3", Level::Info, 8);
+    THREAD_LOGGER.assert_last_log("every_third() => 0", Level::Info, 8);
+    assert!(THREAD_LOGGER.is_empty())
+}
+
+#[test]
+fn sample() {
+    test_logger::init();
+    for n in 0..1000 {
+        half(n);
+    }
+    let logs = drain();
+    let outputs = logs.iter().filter(|log| log.level == Level::Info).count();
+    assert!((300..700).contains(&outputs), "{} outputs logged", outputs);
+    assert!((300..700).contains(&(logs.len() - outputs)), "{} inputs logged", logs.len() - outputs);
+}
+
+#[test]
+fn max_per_sec() {
+    test_logger::init();
+    for n in 0..10 {
+        limited(n);
+    }
+    // The calls may cross into the next second, in which case it's reset once.
+    let logs = drain();
+    assert!((2..=4).contains(&logs.len()), "{} logged", logs.len());
+    assert_eq!(logs.last().unwrap().msg, "limited() => 0");
+}
+
+#[test]
+fn errors_pass_through() {
+    test_logger::init();
+    assert_eq!(parse("1"), Ok(1));
+    THREAD_LOGGER.assert_last_log("parse() => 1", Level::Info, 24);
+    assert_eq!(parse("2"), Ok(2));
+    assert!(parse("a").is_err());
+    assert!(parse("b").is_err());
+    THREAD_LOGGER.assert_last_log("parse() => ParseIntError { kind: InvalidDigit }", Level::Error, 24);
+    THREAD_LOGGER.assert_last_log("parse() => ParseIntError { kind: InvalidDigit }", Level::Error, 24);
+    assert!(THREAD_LOGGER.is_empty())
+}
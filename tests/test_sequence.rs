@@ -0,0 +1,42 @@
+mod test_logger;
+
+use crate::test_logger::THREAD_LOGGER;
+use log::Level;
+use log_derive::logfn;
+
+#[logfn(Debug, each = "Trace", summary = "Info")]
+fn evens(limit: u32) -> impl Iterator<Item = u32> {
+    (0..limit).filter(|n| n % 2 == 0)
+}
+
+#[logfn(summary = "Info")]
+fn words(text: &'static str) -> impl Iterator<Item = &'static str> {
+    text.split(' ')
+}
+
+#[test]
+fn iterator_items() {
+    test_logger::init();
+    assert_eq!(evens(5).collect::<Vec<_>>(), vec![0, 2, 4]);
+    let summary = THREAD_LOGGER.pop_log();
+    assert!(summary.msg.starts_with("evens() finished after 3 items, ts="));
+    assert_eq!(summary.level, Level::Info);
+    THREAD_LOGGER.assert_last_log("evens() yielded 4", Level::Trace, 8);
+    THREAD_LOGGER.assert_last_log("evens() yielded 2", Level::Trace, 8);
+    THREAD_LOGGER.assert_last_log("evens() yielded 0", Level::Trace, 8);
+    assert!(THREAD_LOGGER.is_empty())
+}
+
+#[test]
+fn iterator_summary_only() {
+    test_logger::init();
+    let mut iter = words("a b");
+    assert_eq!(iter.next(), Some("a"));
+    assert!(THREAD_LOGGER.is_empty());
+    assert_eq!(iter.next(), Some("b"));
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next(), None);
+    let summary = THREAD_LOGGER.pop_log();
+    assert!(summary.msg.starts_with("words() finished after 2 items, ts="));
+    assert!(THREAD_LOGGER.is_empty())
+}
@@ -0,0 +1,100 @@
+mod test_logger;
+
+use crate::test_logger::THREAD_LOGGER;
+use log::Level;
+use log_derive::{logfn, logfn_all, logfn_inputs, logfn_skip};
+
+trait Shape: std::fmt::Debug {
+    #[logfn(Info)]
+    #[logfn_inputs(Debug)]
+    fn scale(&self, factor: u32) -> u32;
+
+    #[logfn(Info)]
+    fn double_area(&self) -> u32 {
+        self.scale(2)
+    }
+}
+
+#[derive(Debug)]
+struct Square(u32);
+
+impl Shape for Square {
+    fn scale_impl(&self, factor: u32) -> u32 {
+        self.0 * self.0 * factor
+    }
+}
+
+#[logfn_all(Trace)]
+trait Storage: std::fmt::Debug {
+    fn get(&self, key: &str) -> Option<u32>;
+
+    #[logfn_skip]
+    fn len(&self) -> usize;
+
+    fn contains(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+}
+
+#[derive(Debug)]
+struct Single(&'static str, u32);
+
+impl Storage for Single {
+    fn get_impl(&self, key: &str) -> Option<u32> {
+        if key == self.0 {
+            Some(self.1)
+        } else {
+            None
+        }
+    }
+
+    fn len(&self) -> usize {
+        1
+    }
+}
+
+#[test]
+fn required_and_default_methods() {
+    test_logger::init();
+    let square = Square(3);
+    assert_eq!(square.double_area(), 18);
+    THREAD_LOGGER.assert_last_log("double_area() => 18", Level::Info, 13);
+    THREAD_LOGGER.assert_last_log("scale() => 18", Level::Info, 10);
+    assert_eq!(THREAD_LOGGER.pop_log().msg, "scale(self: Square(3),factor: 2)");
+    assert!(THREAD_LOGGER.is_empty())
+}
+
+#[test]
+fn trait_definition() {
+    test_logger::init();
+    let storage = Single("a", 1);
+    assert_eq!(storage.len(), 1);
+    assert!(THREAD_LOGGER.is_empty());
+    assert!(!storage.contains("b"));
+    THREAD_LOGGER.assert_last_log("contains() => false", Level::Trace, 34);
+    THREAD_LOGGER.assert_last_log("get() => None", Level::Trace, 29);
+    THREAD_LOGGER.assert_last_log("get(self: Single(\"a\", 1),key: \"b\")", Level::Trace, 29);
+    THREAD_LOGGER.assert_last_log("contains(self: Single(\"a\", 1),key: \"b\")", Level::Trace, 34);
+    assert!(THREAD_LOGGER.is_empty())
+}
+
+trait Layout {
+    #[logfn(Info)]
+    fn size_of<T>() -> usize;
+}
+
+struct Native;
+
+impl Layout for Native {
+    fn size_of_impl<T>() -> usize {
+        std::mem::size_of::<T>()
+    }
+}
+
+#[test]
+fn generic_required_method() {
+    test_logger::init();
+    assert_eq!(Native::size_of::<u32>(), 4);
+    THREAD_LOGGER.assert_last_log("size_of() => 4", Level::Info, 83);
+    assert!(THREAD_LOGGER.is_empty())
+}
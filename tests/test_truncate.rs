@@ -0,0 +1,36 @@
+mod test_logger;
+
+use crate::test_logger::THREAD_LOGGER;
+use log::Level;
+use log_derive::{logfn, logfn_inputs};
+
+#[logfn(Info, max_len = 16)]
+#[logfn_inputs(Debug, max_len = 4)]
+fn buffer(len: usize) -> Vec<u8> {
+    vec![0; len]
+}
+
+#[logfn(Info, fmt = "name: {}", max_len = 5)]
+fn name(name: &str) -> String {
+    name.to_owned()
+}
+
+#[test]
+fn truncated() {
+    test_logger::init();
+    assert_eq!(buffer(1 << 20).len(), 1 << 20);
+    THREAD_LOGGER.assert_last_log("buffer() => [0, 0, 0, 0, 0, … (3145728 bytes)", Level::Info, 9);
+    THREAD_LOGGER.assert_last_log("buffer(len: 1048… (7 bytes))", Level::Debug, 9);
+    assert_eq!(buffer(2), [0, 0]);
+    THREAD_LOGGER.assert_last_log("buffer() => [0, 0]", Level::Info, 9);
+    THREAD_LOGGER.assert_last_log("buffer(len: 2)", Level::Debug, 9);
+    assert!(THREAD_LOGGER.is_empty())
+}
+
+#[test]
+fn char_boundary() {
+    test_logger::init();
+    assert_eq!(name("héllo"), "héllo");
+    THREAD_LOGGER.assert_last_log("name: héll… (6 bytes)", Level::Info, 14);
+    assert!(THREAD_LOGGER.is_empty())
+}
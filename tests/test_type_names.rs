@@ -0,0 +1,41 @@
+mod test_logger;
+
+use crate::test_logger::THREAD_LOGGER;
+use log::Level;
+use log_derive::{logfn, logfn_inputs};
+use std::fmt::Debug;
+
+#[logfn(Info, type_names = true)]
+#[logfn_inputs(Debug, type_names = true)]
+fn convert<T: Debug, U: From<T> + Debug>(value: T) -> U {
+    U::from(value)
+}
+
+#[logfn(ok = "Info", err = "Warn", type_names = true)]
+fn parse<T: std::str::FromStr + Debug>(s: &str) -> Result<T, T::Err>
+where
+    T::Err: Debug,
+{
+    s.parse()
+}
+
+#[test]
+fn type_names_entry_and_exit() {
+    test_logger::init();
+
+    assert_eq!(convert::<u8, u32>(7), 7);
+    THREAD_LOGGER.assert_last_log("convert() => 7, T=u8, U=u32", Level::Info, 10);
+    THREAD_LOGGER.assert_last_log("convert(value: 7), T=u8, U=u32", Level::Debug, 10);
+    assert!(THREAD_LOGGER.is_empty())
+}
+
+#[test]
+fn type_names_result() {
+    test_logger::init();
+
+    assert_eq!(parse::<i16>("-1"), Ok(-1));
+    THREAD_LOGGER.assert_last_log("parse() => -1, T=i16", Level::Info, 15);
+    assert!(parse::<bool>("maybe").is_err());
+    THREAD_LOGGER.assert_last_log("parse() => ParseBoolError, T=bool", Level::Warn, 15);
+    assert!(THREAD_LOGGER.is_empty())
+}
@@ -0,0 +1,55 @@
+mod test_logger;
+
+use crate::test_logger::THREAD_LOGGER;
+use log::Level;
+use log_derive::{logfn, logfn_inputs};
+
+#[logfn(Info, when = "result.len() > 3")]
+fn repeat(s: &str, n: usize) -> String {
+    s.repeat(n)
+}
+
+#[logfn(Warn, when = "result.is_err() || attempt == 0")]
+fn parse(s: &str, attempt: u8) -> Result<u32, std::num::ParseIntError> {
+    s.parse()
+}
+
+#[logfn_inputs(Debug, when = "user_id == 0")]
+fn lookup(user_id: u32) -> bool {
+    user_id != 0
+}
+
+#[test]
+fn when_result() {
+    test_logger::init();
+
+    assert_eq!(repeat("ab", 1), "ab");
+    assert!(THREAD_LOGGER.is_empty());
+    assert_eq!(repeat("ab", 2), "abab");
+    THREAD_LOGGER.assert_last_log("repeat() => \"abab\"", Level::Info, 8);
+    assert!(THREAD_LOGGER.is_empty())
+}
+
+#[test]
+fn when_result_and_inputs() {
+    test_logger::init();
+
+    assert_eq!(parse("1", 1), Ok(1));
+    assert!(THREAD_LOGGER.is_empty());
+    assert_eq!(parse("2", 0), Ok(2));
+    THREAD_LOGGER.assert_last_log("parse() => 2", Level::Warn, 13);
+    assert!(parse("x", 1).is_err());
+    THREAD_LOGGER.assert_last_log("parse() => ParseIntError { kind: InvalidDigit }", Level::Warn, 13);
+    assert!(THREAD_LOGGER.is_empty())
+}
+
+#[test]
+fn when_inputs() {
+    test_logger::init();
+
+    assert!(lookup(7));
+    assert!(THREAD_LOGGER.is_empty());
+    assert!(!lookup(0));
+    THREAD_LOGGER.assert_last_log("lookup(user_id: 0)", Level::Debug, 18);
+    assert!(THREAD_LOGGER.is_empty())
+}
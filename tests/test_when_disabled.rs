@@ -0,0 +1,58 @@
+// The max level is global, so this is the only test in its binary.
+mod test_logger;
+
+use crate::test_logger::THREAD_LOGGER;
+use log::LevelFilter;
+use log_derive::{logfn, logfn_inputs};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static CHECKS: AtomicUsize = AtomicUsize::new(0);
+
+fn check() -> bool {
+    CHECKS.fetch_add(1, Ordering::Relaxed);
+    true
+}
+
+#[logfn(Trace, when = "check()")]
+#[logfn_inputs(Trace, when = "check()")]
+fn add(a: u32, b: u32) -> u32 {
+    a + b
+}
+
+#[logfn(Trace, registry = true, when = "check()")]
+fn sub(a: u32, b: u32) -> u32 {
+    a - b
+}
+
+fn classify(_: &u32) -> log::Level {
+    log::Level::Trace
+}
+
+// The level is chosen after the condition, so it's always evaluated.
+#[logfn(level_fn = "classify", when = "check()")]
+fn mul(a: u32, b: u32) -> u32 {
+    a * b
+}
+
+#[test]
+fn when_skipped_if_disabled() {
+    test_logger::init();
+    log::set_max_level(LevelFilter::Error);
+
+    assert_eq!(add(1, 2), 3);
+    assert_eq!(sub(2, 1), 1);
+    assert_eq!(CHECKS.load(Ordering::Relaxed), 0);
+    assert_eq!(mul(2, 3), 6);
+    assert_eq!(CHECKS.load(Ordering::Relaxed), 1);
+    assert!(THREAD_LOGGER.is_empty());
+
+    log::set_max_level(LevelFilter::Trace);
+    assert_eq!(add(1, 2), 3);
+    assert_eq!(CHECKS.load(Ordering::Relaxed), 3);
+    assert_eq!(THREAD_LOGGER.pop_log().msg, "add() => 3");
+    assert_eq!(THREAD_LOGGER.pop_log().msg, "add(a: 1,b: 2)");
+    assert_eq!(sub(2, 1), 1);
+    assert_eq!(CHECKS.load(Ordering::Relaxed), 4);
+    assert_eq!(THREAD_LOGGER.pop_log().msg, "sub() => 1");
+    assert!(THREAD_LOGGER.is_empty())
+}